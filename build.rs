@@ -0,0 +1,119 @@
+// Generates the opcode tables (size, cycles taken/not taken, flags, operands and handler)
+// from resources/opcodes/Opcodes.json, included by src/console/instruction.rs.
+
+use std::{env, fs};
+use std::path::Path;
+use serde_json::Value;
+
+const OPCODES_FILEPATH: &str = "resources/opcodes/Opcodes.json";
+const OUTPUT_FILENAME: &str = "instructions.rs";
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={}", OPCODES_FILEPATH);
+
+    let json = fs::read_to_string(OPCODES_FILEPATH)
+        .unwrap_or_else(|e| panic!("Failed to read {}: {}", OPCODES_FILEPATH, e));
+    let opcodes: Value = serde_json::from_str(&json)
+        .unwrap_or_else(|e| panic!("Failed to parse {}: {}", OPCODES_FILEPATH, e));
+
+    let mut output = format!("// Generated by build.rs from {}, do not edit.\n\n", OPCODES_FILEPATH);
+    output += "// Unprefixed opcodes, indexed by opcode. 0xCB is consumed by the CPU as the prefix byte and never dispatched.\n";
+    output += &generate_table(&opcodes["unprefixed"], "INSTRUCTIONS", 0x0000);
+    output += "\n// CB-prefixed opcodes, indexed by the byte following the prefix.\n";
+    output += &generate_table(&opcodes["cbprefixed"], "CB_INSTRUCTIONS", 0xCB00);
+
+    let output_path = Path::new(&env::var("OUT_DIR").unwrap()).join(OUTPUT_FILENAME);
+    fs::write(output_path, output).unwrap();
+}
+
+fn generate_table(table: &Value, name: &str, prefix: u16) -> String {
+    let mut output = format!("pub(crate) static {}: [InstructionInfo; 256] = [\n", name);
+
+    for i in 0..=0xFFu16 {
+        let key = format!("0x{:02X}", i);
+        let entry = &table[key.as_str()];
+        if entry.is_null() {
+            panic!("{} is missing opcode {}", OPCODES_FILEPATH, key);
+        }
+
+        let opcode = prefix | i;
+        let cycles: Vec<u64> = entry["cycles"].as_array().unwrap()
+            .iter().map(|c| c.as_u64().unwrap()).collect();
+        // Conditional instructions list [taken, not taken]
+        let cycles_taken = cycles[0];
+        let cycles_not_taken = *cycles.last().unwrap();
+
+        let flags = &entry["flags"];
+
+        output += &format!(
+            "    InstructionInfo {{ opcode: {:#06X}, mnemonic: {:?}, size: {}, cycles: {}, cycles_not_taken: {}, \
+                flags: ['{}', '{}', '{}', '{}'], operands: &[{}], _fn: InstructionInfo::{} }},\n",
+            opcode,
+            mnemonic(entry),
+            entry["bytes"].as_u64().unwrap(),
+            cycles_taken,
+            cycles_not_taken,
+            flags["Z"].as_str().unwrap(),
+            flags["N"].as_str().unwrap(),
+            flags["H"].as_str().unwrap(),
+            flags["C"].as_str().unwrap(),
+            operands(entry),
+            handler(opcode, entry["mnemonic"].as_str().unwrap()),
+        );
+    }
+
+    output += "];\n";
+    output
+}
+
+// Example: "LD (HL+),A", "LD HL,SP+r8", "JR NZ,r8"
+fn mnemonic(entry: &Value) -> String {
+    let mut result = String::from(entry["mnemonic"].as_str().unwrap());
+
+    for (i, operand) in entry["operands"].as_array().unwrap().iter().enumerate() {
+        let mut name = String::from(operand["name"].as_str().unwrap());
+        if operand["increment"].as_bool().unwrap_or(false) {
+            name.push('+');
+        }
+        if operand["decrement"].as_bool().unwrap_or(false) {
+            name.push('-');
+        }
+
+        let is_immediate = operand["immediate"].as_bool().unwrap_or(true);
+        if !is_immediate {
+            name = format!("({})", name);
+        }
+
+        if i == 0 {
+            result.push(' ');
+        } else if !result.ends_with('+') {
+            result.push(',');
+        }
+        result += &name;
+    }
+
+    result
+}
+
+fn operands(entry: &Value) -> String {
+    entry["operands"].as_array().unwrap()
+        .iter()
+        .map(|operand| format!(
+            "Operand {{ name: {:?}, bytes: {}, immediate: {} }}",
+            operand["name"].as_str().unwrap(),
+            operand["bytes"].as_u64().unwrap_or(0),
+            operand["immediate"].as_bool().unwrap_or(true)))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn handler(opcode: u16, mnemonic: &str) -> String {
+    if mnemonic.starts_with("ILLEGAL") {
+        String::from("invalid")
+    } else if mnemonic == "PREFIX" {
+        String::from("unimplemented")
+    } else {
+        format!("op_{:04x}", opcode)
+    }
+}
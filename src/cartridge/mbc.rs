@@ -38,9 +38,8 @@ impl Mbc {
         };
 
         let cartridge_type = CartridgeType::from_u8(data[CARTRIDGE_TYPE_ADDRESS]);
-        println!("CARTRIDGE TYPE = {:?}", cartridge_type);
 
-        let mbc = match cartridge_type {
+        match cartridge_type {
             CartridgeType::NoMbc { .. } => Mbc::None,
             CartridgeType::Mbc1 { is_multicart, .. } => Mbc::Mbc1 { mbc: Mbc1::new(is_multicart) },
             CartridgeType::Mbc3 { .. } => Mbc::Mbc3 { mbc: Mbc3::new(ram_size) },
@@ -48,9 +47,6 @@ impl Mbc {
             | CartridgeType::Mbc5 { .. }
             | CartridgeType::Huc1 { .. }
             | _ => panic!("UNIMPLEMENTED CartridgeType {:?}", cartridge_type),
-        };
-        println!("CARTRIDGE MBC TYPE = {:?}", mbc);
-
-        mbc
+        }
     }
 }
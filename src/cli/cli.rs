@@ -4,7 +4,10 @@ use std::path::{Path, PathBuf};
 
 use crate::cartridge::cartridge::Cartridge;
 use crate::console::console::Console;
-use crate::console::{disassembler, display};
+use crate::console::disassembler;
+use crate::console::display::DisplayOptions;
+use crate::console::recorder::RecordFormat;
+use crate::console::upscaler::UpscalerKind;
 
 pub(crate) const BOOTROM_FILEPATH: &str = "./roms/bootrom/dmg.bin";
 pub(crate) const ROM_DIR: &str = "./roms";
@@ -13,11 +16,21 @@ const NO_ROM_STRING: &str = "norom";
 const SKIP_BOOT_FLAG_STRING: &str = "skipboot";
 const DEBUG_FLAG_STRING: &str = "debug";
 const PRINT_CPU_FLAG_STRING: &str = "printcpu";
+const VSYNC_FLAG_STRING: &str = "vsync";
+const INTEGER_SCALE_FLAG_STRING: &str = "integerscale";
+const SCALE_OPTION_STRING: &str = "--scale";
+const SCREENSHOT_SCALE_OPTION_STRING: &str = "--screenshot-scale";
+const SCREENSHOT_AT_FRAME_OPTION_STRING: &str = "--screenshot-at-frame";
+const RECORD_FORMAT_OPTION_STRING: &str = "--record-format";
+const GIF_SECONDS_OPTION_STRING: &str = "--gif-seconds";
+const UPSCALER_OPTION_STRING: &str = "--upscaler";
 
 struct EmuArgs {
     skip_boot: bool,
     debug_enabled: bool,
     print_cpu_instrs: bool,
+    display_options: DisplayOptions,
+    screenshot_at_frame: Option<(u64, PathBuf)>, // Run headless, save this frame and exit
     rom_filepath: String,
 }
 
@@ -34,12 +47,35 @@ impl EmuArgs {
         let mut skip_boot = false;
         let mut debug_enabled = false;
         let mut print_cpu_instrs = false;
+        let mut display_options = DisplayOptions::new();
+        let mut screenshot_at_frame = None;
 
         if args.len() > 1 {
-            rom_filepath = args[1].clone();
+            if !args[1].starts_with("--") {
+                rom_filepath = args[1].clone();
+            }
             skip_boot = args.contains(&String::from(SKIP_BOOT_FLAG_STRING));
             debug_enabled = args.contains(&String::from(DEBUG_FLAG_STRING));
             print_cpu_instrs = args.contains(&String::from(PRINT_CPU_FLAG_STRING));
+            display_options.vsync = args.contains(&String::from(VSYNC_FLAG_STRING));
+            display_options.integer_scale = args.contains(&String::from(INTEGER_SCALE_FLAG_STRING));
+            if let Some(scale) = EmuArgs::parse_positive(&args, SCALE_OPTION_STRING) {
+                display_options.window_scale = scale;
+            }
+            if let Some(scale) = EmuArgs::parse_positive(&args, SCREENSHOT_SCALE_OPTION_STRING) {
+                display_options.screenshot_scale = scale;
+            }
+            if let Some(seconds) = EmuArgs::parse_positive(&args, GIF_SECONDS_OPTION_STRING) {
+                display_options.gif_seconds = seconds;
+            }
+            if let Some(format) = EmuArgs::parse_record_format(&args) {
+                display_options.record_format = format;
+            }
+            if let Some(kind) = EmuArgs::parse_upscaler(&args) {
+                display_options.upscaler = kind;
+            }
+            screenshot_at_frame = EmuArgs::parse_screenshot_at_frame(&args);
+            display_options.is_headless = screenshot_at_frame.is_some();
         }
 
         EmuArgs {
@@ -47,6 +83,54 @@ impl EmuArgs {
             skip_boot,
             debug_enabled,
             print_cpu_instrs,
+            display_options,
+            screenshot_at_frame,
+        }
+    }
+
+    // `<option> N`, e.g. `--scale 3`
+    fn parse_positive(args: &[String], option: &str) -> Option<u32> {
+        let i = args.iter().position(|arg| arg == option)?;
+        match args.get(i + 1).and_then(|value| value.parse::<u32>().ok()) {
+            Some(value) if value > 0 => Option::from(value),
+            _ => {
+                println!("WARNING: '{}' expects a positive whole number, using the default.", option);
+                None
+            }
+        }
+    }
+
+    // `--record-format y4m|rgb`
+    fn parse_record_format(args: &[String]) -> Option<RecordFormat> {
+        let i = args.iter().position(|arg| arg == RECORD_FORMAT_OPTION_STRING)?;
+        let format = args.get(i + 1).and_then(|value| RecordFormat::parse(value));
+        if format.is_none() {
+            println!("WARNING: '{}' expects 'y4m' or 'rgb', using the default.", RECORD_FORMAT_OPTION_STRING);
+        }
+        format
+    }
+
+    // `--upscaler nearest|scale2x|scale3x|eagle|xbr`
+    fn parse_upscaler(args: &[String]) -> Option<UpscalerKind> {
+        let i = args.iter().position(|arg| arg == UPSCALER_OPTION_STRING)?;
+        let kind = args.get(i + 1).and_then(|value| UpscalerKind::parse(value));
+        if kind.is_none() {
+            println!("WARNING: '{}' expects 'nearest', 'scale2x', 'scale3x', 'eagle' or 'xbr', using the default.", UPSCALER_OPTION_STRING);
+        }
+        kind
+    }
+
+    // `--screenshot-at-frame N out.png`
+    fn parse_screenshot_at_frame(args: &[String]) -> Option<(u64, PathBuf)> {
+        let i = args.iter().position(|arg| arg == SCREENSHOT_AT_FRAME_OPTION_STRING)?;
+        let frame = args.get(i + 1).and_then(|value| value.parse::<u64>().ok());
+        let path = args.get(i + 2).map(PathBuf::from);
+        match (frame, path) {
+            (Some(frame), Some(path)) => Option::from((frame, path)),
+            _ => {
+                println!("WARNING: '{}' expects a frame number and an output file, ignoring it.", SCREENSHOT_AT_FRAME_OPTION_STRING);
+                None
+            }
         }
     }
 }
@@ -135,32 +219,44 @@ fn disassemble_rom(filepath: &str, out_path: &str) {
     disassembler::disassemble_to_output_file(&cartridge.data, out_path, file_name.as_str());
 }
 
-fn run_no_rom(args: &EmuArgs, window_scale: u32) {
+fn run_no_rom(args: &EmuArgs) {
     // Don't allow skip boot if running without a cartridge
     let skip_boot = false;
     let mut gamboy = Console::new(
         "GAMBOY",
-        window_scale,
+        &args.display_options,
         args.debug_enabled,
         args.print_cpu_instrs,
         skip_boot,
         None
     );
-    gamboy.run();
+    start(&mut gamboy, args);
 }
 
-fn run_rom(args: &EmuArgs, window_scale: u32) {
+fn run_rom(args: &EmuArgs) {
     disassemble_rom(args.rom_filepath.as_str(), DISASSEMBLE_OUTPUT_DIR);
     let cartridge = Cartridge::new(args.rom_filepath.as_ref());
     let mut gamboy = Console::new(
         "GAMBOY",
-        window_scale,
+        &args.display_options,
         args.debug_enabled,
         args.print_cpu_instrs,
         args.skip_boot,
         Some(cartridge)
     );
-    gamboy.run();
+    start(&mut gamboy, args);
+}
+
+fn start(gamboy: &mut Console, args: &EmuArgs) {
+    match args.screenshot_at_frame {
+        Some((frame, ref path)) => {
+            if let Err(error) = gamboy.run_to_screenshot(frame, path) {
+                println!("{}", error);
+                std::process::exit(1);
+            }
+        }
+        None => gamboy.run(),
+    }
 }
 
 pub(crate) fn run() {
@@ -184,8 +280,8 @@ pub(crate) fn run() {
     }
 
     if args.rom_filepath.eq_ignore_ascii_case(NO_ROM_STRING) {
-        run_no_rom(&args, display::WINDOW_SCALE);
+        run_no_rom(&args);
     } else {
-        run_rom(&args, display::WINDOW_SCALE);
+        run_rom(&args);
     }
 }
@@ -0,0 +1,203 @@
+use crate::console::interrupts::InterruptRegBit;
+use crate::console::mmu;
+use crate::console::mmu::{Caller, Mmu};
+use crate::console::ppu::Ppu;
+use crate::console::scheduler::{EventKind, Scheduler};
+use crate::console::serial::Serial;
+use crate::console::timer::Timer;
+
+pub(crate) const M_CYCLE: u16 = 4;
+
+// Everything the CPU can reach while executing an instruction.
+// Each CPU memory access takes one M-cycle (4 T-cycles), and the rest of the system is ticked
+// before the access happens, so reads and writes land at their real sub-instruction times.
+pub(crate) struct Bus<'a> {
+    pub(crate) mmu: &'a mut Mmu,
+    ppu: &'a mut Ppu,
+    timer: &'a mut Timer,
+    serial: &'a mut Serial,
+    scheduler: &'a mut Scheduler,
+    pub(crate) elapsed: i16, // T-cycles ticked since the bus was created
+    #[cfg(test)]
+    pub(crate) accesses: Vec<(u16, i16)>, // Address and `elapsed` of every CPU access, in order
+}
+
+impl<'a> Bus<'a> {
+    pub(crate) fn new(
+            mmu: &'a mut Mmu,
+            ppu: &'a mut Ppu,
+            timer: &'a mut Timer,
+            serial: &'a mut Serial,
+            scheduler: &'a mut Scheduler) -> Bus<'a> {
+        Bus {
+            mmu,
+            ppu,
+            timer,
+            serial,
+            scheduler,
+            elapsed: 0,
+            #[cfg(test)]
+            accesses: vec![],
+        }
+    }
+
+    // Advance time, running the events that fall due in order.
+    pub(crate) fn tick(&mut self, cycles: u16) {
+        let until = self.scheduler.now + cycles as u64;
+        while let Some(event) = self.scheduler.pop_due(until) {
+            self.handle_event(event);
+        }
+        self.scheduler.now = until;
+        self.elapsed += cycles as i16;
+    }
+
+    // Used while the CPU is halted or locked up: nothing can happen until the next event,
+    // so skip straight to it (in whole M-cycles, and no further than `max_cycles`).
+    pub(crate) fn tick_to_next_event(&mut self, max_cycles: u16) -> u16 {
+        let cycles = match self.scheduler.cycles_until_next() {
+            Some(cycles) => (cycles.div_ceil(M_CYCLE as u64) * M_CYCLE as u64)
+                .clamp(M_CYCLE as u64, max_cycles as u64) as u16,
+            None => max_cycles,
+        };
+        self.tick(cycles);
+        cycles
+    }
+
+    fn handle_event(&mut self, event: EventKind) {
+        let now = self.scheduler.now;
+        match event {
+            EventKind::PpuModeChange => {
+                self.ppu.oam_dma(self.mmu, now);
+                self.ppu.change_mode(self.mmu, self.scheduler);
+            }
+            EventKind::TimerOverflow => {
+                if self.timer.overflow(self.mmu, self.scheduler) {
+                    self.mmu.request_interrupt(InterruptRegBit::Timer);
+                }
+            }
+            EventKind::OamDmaComplete => {
+                self.ppu.oam_dma(self.mmu, now);
+            }
+            EventKind::SerialBit => {
+                if self.serial.shift_bit(self.mmu, self.scheduler) {
+                    self.mmu.request_interrupt(InterruptRegBit::Serial);
+                }
+            }
+        }
+    }
+
+    // Components that run lazily are brought up to date before the CPU can observe them.
+    fn sync(&mut self, address: u16) {
+        self.ppu.oam_dma(self.mmu, self.scheduler.now);
+        if matches!(address, 0x8000..=0x9FFF | mmu::OAM_START..=mmu::OAM_END | mmu::LCD_CONTROL_REG..=mmu::WX_REG) {
+            self.ppu.sync(self.mmu, self.scheduler);
+        }
+        if (mmu::DIV_REG..=mmu::TAC_REG).contains(&address) {
+            self.timer.sync(self.mmu, self.scheduler.now);
+        }
+    }
+
+    // STOP resets DIV without a memory access, the timer still has to catch up before the
+    // system clock is cleared, and count its next overflow from there.
+    pub(crate) fn reset_div(&mut self) {
+        self.timer.sync(self.mmu, self.scheduler.now);
+        self.mmu.write_8(mmu::DIV_REG, 0, Caller::CPU);
+        self.timer.reschedule(self.mmu, self.scheduler);
+    }
+
+    // During OAM DMA only HRAM and the IO registers are reachable, the rest of the bus is in use
+    fn is_blocked(&self, address: u16) -> bool {
+        address < 0xFF00 && self.ppu.is_oam_dma_blocking(self.scheduler.now)
+    }
+
+    pub(crate) fn read_8(&mut self, address: u16, caller: Caller) -> u8 {
+        self.tick(M_CYCLE);
+        #[cfg(test)]
+        self.accesses.push((address, self.elapsed));
+        self.sync(address);
+        if self.is_blocked(address) {
+            return 0xFF;
+        }
+        self.mmu.read_8(address, caller)
+    }
+
+    pub(crate) fn read_16(&mut self, address: u16, caller: Caller) -> u16 {
+        let lo = self.read_8(address, caller);
+        let hi = self.read_8(address.wrapping_add(1), caller);
+        ((hi as u16) << 8) | (lo as u16)
+    }
+
+    pub(crate) fn write_8(&mut self, address: u16, value: u8, caller: Caller) -> u8 {
+        self.tick(M_CYCLE);
+        #[cfg(test)]
+        self.accesses.push((address, self.elapsed));
+        self.sync(address);
+        if self.is_blocked(address) {
+            return 0xFF;
+        }
+        let result = self.mmu.write_8(address, value, caller);
+
+        // Writes that change when the next events are due, or the interrupt lines
+        match address {
+            mmu::SC_REG => self.serial.control_written(self.mmu, self.scheduler),
+            mmu::DIV_REG..=mmu::TAC_REG => self.timer.reschedule(self.mmu, self.scheduler),
+            mmu::LCD_CONTROL_REG => self.ppu.lcd_control_written(self.mmu, self.scheduler),
+            mmu::LCD_STATUS_REG => self.ppu.lcd_status_written(self.mmu),
+            mmu::LYC_REG => self.ppu.compare_lyc(self.mmu),
+            mmu::DMA_REG => self.ppu.start_oam_dma(self.mmu, self.scheduler),
+            _ => {}
+        }
+
+        result
+    }
+
+    pub(crate) fn write_16(&mut self, address: u16, value: u16, caller: Caller) -> u16 {
+        let lo = self.write_8(address, (value & 0x00FF) as u8, caller);
+        let hi = self.write_8(address.wrapping_add(1), ((value & 0xFF00) >> 8) as u8, caller);
+        ((hi as u16) << 8) | (lo as u16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::console::mmu::{Caller, Mmu};
+    use crate::console::ppu::Ppu;
+    use crate::console::scheduler::Scheduler;
+    use crate::console::serial::Serial;
+    use crate::console::timer::Timer;
+    use super::*;
+
+    const OAM_DMA_M_CYCLES: usize = 160;
+
+    #[test]
+    fn oam_dma_blocks_reads_below_ff00_for_the_transfer() {
+        let mut mmu = Mmu::new(None, true);
+        let mut ppu = Ppu::new();
+        let mut timer = Timer::new();
+        let mut serial = Serial::new();
+        let mut scheduler = Scheduler::new();
+        mmu.write_8(0xC000, 0x42, Caller::CPU);
+        mmu.write_8(0xFF80, 0x24, Caller::CPU);
+
+        let mut bus = Bus::new(&mut mmu, &mut ppu, &mut timer, &mut serial, &mut scheduler);
+        bus.write_8(mmu::DMA_REG, 0xC0, Caller::CPU);
+
+        // The startup M-cycle still reaches WRAM, then HRAM is all the CPU can read until the last byte is copied
+        let mut wram_reads = vec![];
+        for _ in 0..OAM_DMA_M_CYCLES + 2 {
+            wram_reads.push(bus.read_8(0xC000, Caller::CPU));
+        }
+        let mut expected = vec![0x42];
+        expected.extend([0xFF; OAM_DMA_M_CYCLES]);
+        expected.push(0x42);
+        assert_eq!(wram_reads, expected);
+
+        let mut bus = Bus::new(&mut mmu, &mut ppu, &mut timer, &mut serial, &mut scheduler);
+        bus.write_8(mmu::DMA_REG, 0xC0, Caller::CPU);
+        bus.tick(M_CYCLE);
+        for _ in 0..OAM_DMA_M_CYCLES {
+            assert_eq!(bus.read_8(0xFF80, Caller::CPU), 0x24);
+        }
+        assert_eq!(mmu.read_8(mmu::OAM_START, Caller::CPU), 0x42);
+    }
+}
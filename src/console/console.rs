@@ -1,32 +1,49 @@
+use std::path::Path;
 use std::thread::sleep;
 use std::time::{Duration, Instant};
 use sdl2::Sdl;
 
 use crate::cartridge::cartridge::Cartridge;
+use crate::console::bus::Bus;
 use crate::console::cpu::Cpu;
 use crate::console::debugger::Debugger;
-use crate::console::display::Display;
+use crate::console::display::{Display, DisplayOptions};
+use crate::console::framebuffer;
+use crate::console::framebuffer::Palette;
+use crate::console::gif_export;
+use crate::console::lcd_filter::FilterKind;
+use crate::console::palette_config::{PaletteConfig, PALETTE_CONFIG_PATH};
 use crate::console::input::{Callback, Input};
 use crate::console::mmu::{Caller, Mmu};
 use crate::console::ppu::Ppu;
+use crate::console::recorder::{RecordFormat, Recorder};
 use crate::console::cpu_registers::{CpuRegIndex};
 use crate::console::interrupts::InterruptRegBit;
 use crate::console::mmu;
+use crate::console::scheduler::Scheduler;
+use crate::console::screenshot;
+use crate::console::serial::Serial;
 use crate::console::timer::Timer;
 
+// T-cycles per second
+pub(crate) const CLOCK_HZ: u64 = 4_194_304;
 // const CYCLES_PER_FRAME: u64 = 69_905;
-const CYCLES_PER_FRAME: u64 = 100_000;
+pub(crate) const CYCLES_PER_FRAME: u64 = 100_000; // Emulated T-cycles between presented frames
 const FRAMES_PER_SECOND: u64 = 60;
 
 pub(crate) struct Console {
-    cycles: i16,
     skip_boot: bool,
     timer: Timer,
     cpu: Cpu,
     mmu: Mmu,
     ppu: Ppu,
-    input: Input,
-    display: Display,
+    serial: Serial,
+    scheduler: Scheduler,
+    input: Option<Input>,
+    display: Option<Display>, // Both None when running headless
+    screenshot_scale: u32,
+    record_format: RecordFormat,
+    recorder: Option<Recorder>,
     debugger: Option<Debugger>,
     // perf
     total_cycles: u128,
@@ -37,17 +54,19 @@ pub(crate) struct Console {
 impl Console {
     pub(crate) fn new(
             window_title: &str,
-            window_scale: u32,
+            display_options: &DisplayOptions,
             debug: bool,
             cpu_debug_print: bool,
             skip_boot: bool,
             cartridge: Option<Cartridge>) -> Console {
         let timer = Timer::new();
-        let ppu = Ppu::new();
+        let mut ppu = Ppu::new();
+        ppu.lcd.set_layers_enabled(true); // Display colors each layer with its own palette
+        let cartridge_type = cartridge.as_ref()
+            .and_then(|cartridge| cartridge.header.as_ref())
+            .map(|header| format!("Cartridge: {:?}", header.cartridge_type));
         let mmu = Mmu::new(cartridge, skip_boot);
         let cpu = Cpu::new(cpu_debug_print);
-        let mut sdl_context: Sdl = sdl2::init().unwrap();
-        let input = Input::new(&mut sdl_context);
 
         let debugger = if debug {
             Option::from(Debugger::new())
@@ -55,30 +74,80 @@ impl Console {
             None
         };
 
-        let display = Display::new(
-            window_scale,
-            window_title,
-            &sdl_context,
-            ppu.lcd.width,
-            ppu.lcd.height);
+        let (input, display) = if display_options.is_headless {
+            (None, None)
+        } else {
+            let mut sdl_context: Sdl = sdl2::init().unwrap();
+            let input = Input::new(&mut sdl_context);
+            let display = Display::new(
+                display_options,
+                window_title,
+                &sdl_context,
+                ppu.lcd.width,
+                ppu.lcd.height);
+            (Option::from(input), Option::from(display))
+        };
 
-        Console {
-            cycles: 0,
+        let mut console = Console {
             skip_boot,
             timer,
             cpu,
             mmu,
             ppu,
+            serial: Serial::new(),
+            scheduler: Scheduler::new(),
             input,
             display,
+            screenshot_scale: display_options.screenshot_scale,
+            record_format: display_options.record_format,
+            recorder: None,
             debugger,
             total_cycles: 0,
             total_frames: 0,
             total_runtime: 0,
+        };
+        if let Some(text) = cartridge_type {
+            console.notify(&text);
         }
+        console
     }
 
     pub(crate) fn run(&mut self) {
+        self.power_on();
+
+        self.main_loop();
+        if self.recorder.is_some() {
+            self.toggle_recording();
+        }
+
+        // self.debug_print_screen();
+        self.debug_peek();
+    }
+
+    // Headless: run until `frame` frames have been completed, then save the screen and exit.
+    // Frames are only counted while the LCD is on, so give up after the cycles those frames
+    // should have taken (plus a frame of slack) instead of waiting forever on an LCD that stays
+    // off or a CPU that executed STOP.
+    pub(crate) fn run_to_screenshot(&mut self, frame: u64, path: &Path) -> Result<(), String> {
+        self.power_on();
+
+        let max_cycles = (frame + 1) * CYCLES_PER_FRAME;
+        let mut cycles: u64 = 0;
+        while self.ppu.frames < frame {
+            if cycles >= max_cycles {
+                return Err(format!(
+                    "ERROR: Frame {} was never reached, only {} frames were drawn in {} cycles (LCD off or CPU stopped). No screenshot saved.",
+                    frame, self.ppu.frames, cycles));
+            }
+            cycles += self.main_tick() as u64;
+        }
+
+        let palette_config = PaletteConfig::load(PALETTE_CONFIG_PATH);
+        self.save_screenshot(palette_config.active_palette(), path);
+        Ok(())
+    }
+
+    fn power_on(&mut self) {
         if self.skip_boot {
             self.cpu.registers.set_word(CpuRegIndex::AF, 0x01B0);
             self.cpu.registers.set_word(CpuRegIndex::BC, 0x0013);
@@ -87,13 +156,25 @@ impl Console {
             self.cpu.registers.set_word(CpuRegIndex::SP, 0xFFFE);
             self.cpu.registers.set_word(CpuRegIndex::PC, 0x0100);
             self.mmu.write_8(mmu::LCD_CONTROL_REG, 0x91, Caller::CPU); // Enable LCD
+            self.ppu.lcd_control_written(&mut self.mmu, &mut self.scheduler);
             self.mmu.is_booting = false;
         }
+    }
 
-        self.main_loop();
+    fn save_screenshot(&mut self, palette: &Palette, path: &Path) {
+        let rgba = framebuffer::to_rgba(&self.ppu.lcd, palette);
+        match screenshot::save_png(path, &rgba, self.ppu.lcd.width, self.ppu.lcd.height, self.screenshot_scale as usize) {
+            Ok(()) => self.notify(&format!("Saved screenshot '{}'.", path.display())),
+            Err(error) => self.notify(&format!("ERROR: Failed to save screenshot '{}': {}", path.display(), error)),
+        }
+    }
 
-        // self.debug_print_screen();
-        self.debug_peek();
+    // Status for the user, on screen too when there is a window
+    fn notify(&mut self, text: &str) {
+        match self.display {
+            Some(ref mut display) => display.notify(text),
+            None => println!("{}", text),
+        }
     }
 
     fn debug_peek(&mut self) {
@@ -126,13 +207,118 @@ impl Console {
         }
     }
 
+    // Illegal opcodes lock up the CPU (as on hardware), the PPU and timer keep running.
+    fn report_lockup(&mut self, address: u16, opcode: u16) {
+        println!("CPU locked up: illegal opcode {:#04X} at address {:#06X}.", opcode, address);
+        if let Some(ref mut debugger) = self.debugger {
+            debugger.force_break(
+                Option::from(&mut self.cpu),
+                Option::from(&mut self.mmu),
+                Option::from(&mut self.timer),
+                Option::from(vec![]));
+        }
+    }
+
+    fn toggle_recording(&mut self) {
+        match self.recorder.take() {
+            Some(recorder) => {
+                let path = recorder.path().to_path_buf();
+                match recorder.finish() {
+                    Ok(frames) => self.notify(&format!("Saved recording '{}' ({} frames).", path.display(), frames)),
+                    Err(error) => self.notify(&format!("ERROR: Failed to save recording '{}': {}", path.display(), error)),
+                }
+            }
+            None => {
+                match Recorder::start(self.record_format, self.ppu.lcd.width, self.ppu.lcd.height, CYCLES_PER_FRAME) {
+                    Ok(recorder) => {
+                        // There is no APU yet, so there is no audio to record alongside the video
+                        let text = format!("Recording to '{}' (video only, no audio).", recorder.path().display());
+                        self.recorder = Option::from(recorder);
+                        self.notify(&text);
+                    }
+                    Err(error) => self.notify(&format!("ERROR: Failed to start recording: {}", error)),
+                }
+            }
+        }
+    }
+
+    fn record_frame(&mut self) {
+        if let (Some(recorder), Some(display)) = (&mut self.recorder, &self.display) {
+            if let Err(error) = recorder.record(display.rgba()) {
+                let text = format!("ERROR: Failed to record frame, stopping: {}", error);
+                self.recorder = None;
+                self.notify(&text);
+            }
+        }
+    }
+
     fn input_polling(&mut self) -> bool {
-        let callbacks = self.input.poll(&mut self.mmu);
+        let callbacks = match self.input {
+            Some(ref mut input) => input.poll(&mut self.mmu),
+            None => return true,
+        };
 
         for callback in callbacks {
             match callback {
                 Callback::CycleColorPalette => {
-                    self.display.cycle_color_palette();
+                    if let Some(ref mut display) = self.display {
+                        display.cycle_color_palette();
+                    }
+                }
+                Callback::Screenshot => {
+                    if let Some(ref display) = self.display {
+                        let palette = *display.active_palette();
+                        let path = screenshot::timestamped_path(screenshot::SCREENSHOT_DIR, "gamboy", "png");
+                        self.save_screenshot(&palette, &path);
+                    }
+                }
+                Callback::SaveGif => {
+                    if let Some(ref mut display) = self.display {
+                        let path = screenshot::timestamped_path(gif_export::GIF_DIR, "gamboy", "gif");
+                        let text = match display.save_gif(&path) {
+                            Ok(frames) => format!("Saved GIF '{}' ({} frames).", path.display(), frames),
+                            Err(error) => format!("ERROR: Failed to save GIF '{}': {}", path.display(), error),
+                        };
+                        display.notify(&text);
+                    }
+                }
+                Callback::ToggleRecording => {
+                    self.toggle_recording();
+                }
+                Callback::ToggleGhosting => {
+                    if let Some(ref mut display) = self.display {
+                        display.toggle_filter(FilterKind::Ghosting);
+                    }
+                }
+                Callback::ToggleGrid => {
+                    if let Some(ref mut display) = self.display {
+                        display.toggle_filter(FilterKind::Grid);
+                    }
+                }
+                Callback::ToggleColorResponse => {
+                    if let Some(ref mut display) = self.display {
+                        display.toggle_filter(FilterKind::ColorResponse);
+                    }
+                }
+                Callback::CycleUpscaler => {
+                    if let Some(ref mut display) = self.display {
+                        display.cycle_upscaler();
+                    }
+                }
+                Callback::ToggleStats => {
+                    if let Some(ref mut display) = self.display {
+                        display.toggle_stats();
+                    }
+                }
+                Callback::ToggleInputDisplay => {
+                    if let Some(ref mut display) = self.display {
+                        display.toggle_input_display();
+                    }
+                }
+                Callback::ToggleFullscreen => {
+                    if let Some(ref mut display) = self.display {
+                        display.toggle_fullscreen();
+                    }
                 }
                 Callback::DebugBreak => {
                     match self.debugger {
@@ -188,37 +374,35 @@ impl Console {
             return 4;
         }
 
-        // PPU - OAM DMA transfer
-        self.ppu.oam_dma(&mut self.mmu);
+        // STOP - CPU, LCD and timer are stopped until a joypad line goes low
+        if self.cpu.is_stopped {
+            if !self.mmu.is_joypad_line_low() {
+                return 4;
+            }
+            self.cpu.is_stopped = false;
+            self.timer.set_stop_mode(&mut self.mmu, &mut self.scheduler, false);
+        }
 
-        // PPU - step
-        self.ppu.step(self.cycles as u16, &mut self.cpu.interrupts, &mut self.mmu);
+        // CPU - step (execute instruction), running the events that fall due on every M-cycle
+        let was_locked = self.cpu.lockup.is_some();
+        let mut bus = Bus::new(
+            &mut self.mmu, &mut self.ppu, &mut self.timer, &mut self.serial, &mut self.scheduler);
+        let mut cycles = self.cpu.step(&mut bus);
+
+        // CPU - interrupts
+        cycles += self.cpu.handle_interrupts(&mut bus);
 
-        // CPU - step (execute instruction)
-        self.cycles = self.cpu.step(&mut self.mmu);
-        if self.cycles < 0 {
-            // self.debug_print_screen();
-            self.debug_peek();
-            panic!("Console step attempt failed with status {} at address {:#06X}.",
-                self.cycles, self.cpu.registers.get_word(CpuRegIndex::PC));
+        if !was_locked {
+            if let Some((address, opcode)) = self.cpu.lockup {
+                self.report_lockup(address, opcode);
+            }
         }
 
-        // CPU - interrupts
-        self.cycles += self.cpu.handle_interrupts(&mut self.mmu);
-
-        // TIMER
-        // let mut timer_cycles = self.cycles;
-        // while timer_cycles >= 4 {
-        //     timer_cycles -= 4;
-        //     if self.timer.step(&mut self.mmu, 4) {
-        //         self.cpu.interrupts.request(InterruptRegBit::Timer, &mut self.mmu);
-        //     }
-        // }
-        if self.timer.step(&mut self.mmu, self.cycles as u16) {
-            self.cpu.interrupts.request(InterruptRegBit::Timer, &mut self.mmu);
+        if self.cpu.is_stopped {
+            self.timer.set_stop_mode(&mut self.mmu, &mut self.scheduler, true);
         }
 
-        self.cycles as u16
+        cycles as u16
     }
 
     fn main_loop(&mut self) {
@@ -228,7 +412,10 @@ impl Console {
 
         while is_running {
             if cycles_this_frame >= CYCLES_PER_FRAME {
-                self.display.draw(&mut self.ppu);
+                if let Some(display) = &mut self.display {
+                    display.draw(&mut self.ppu, &self.mmu.active_input);
+                }
+                self.record_frame();
 
                 is_running = self.input_polling();
 
@@ -265,7 +452,10 @@ impl Console {
                 }
             } else {
                 // DRAW + POLL
-                self.display.draw(&mut self.ppu);
+                if let Some(display) = &mut self.display {
+                    display.draw(&mut self.ppu, &self.mmu.active_input);
+                }
+                self.record_frame();
                 self.total_frames += 1;
 
                 if !self.input_polling() {
@@ -282,3 +472,62 @@ impl Console {
         self.total_runtime = runtime.as_secs() as u128;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use super::*;
+
+    // Runs a ROM-only cartridge whose program starts at 0x0100
+    fn headless_console(name: &str, program: &[u8]) -> Console {
+        let mut rom = vec![0; 0x8000];
+        rom[0x0100..0x0100 + program.len()].copy_from_slice(program);
+        let rom_path = std::env::temp_dir().join(name);
+        fs::write(&rom_path, &rom).unwrap();
+
+        let mut display_options = DisplayOptions::new();
+        display_options.is_headless = true;
+        Console::new("TEST", &display_options, false, false, true, Some(Cartridge::new(&rom_path)))
+    }
+
+    #[test]
+    fn screenshot_gives_up_when_lcd_stays_off() {
+        // XOR A; LDH (0x40),A; JR -2 - turns the LCD off that skip boot turned on, then spins
+        let mut console = headless_console("lcd_off.gb", &[0xAF, 0xE0, 0x40, 0x18, 0xFE]);
+        let path = std::env::temp_dir().join("lcd_off.png");
+        let _ = fs::remove_file(&path);
+
+        let result = console.run_to_screenshot(5, &path);
+
+        assert!(result.is_err());
+        assert_eq!(console.ppu.frames, 0);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn screenshot_gives_up_when_cpu_stops() {
+        // DI; STOP - no joypad line ever goes low
+        let mut console = headless_console("cpu_stop.gb", &[0xF3, 0x10, 0x00]);
+        let path = std::env::temp_dir().join("cpu_stop.png");
+        let _ = fs::remove_file(&path);
+
+        let result = console.run_to_screenshot(5, &path);
+
+        assert!(result.is_err());
+        assert!(console.cpu.is_stopped);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn stop_leaves_div_reset() {
+        // LD B,0; DEC B; JR NZ,-3 - about 4000 cycles for DIV to count up, then STOP
+        let mut console = headless_console("stop_div.gb", &[0x06, 0x00, 0x05, 0x20, 0xFD, 0x10, 0x00]);
+        console.power_on();
+        while !console.cpu.is_stopped {
+            console.main_tick();
+        }
+
+        assert_eq!(console.mmu.read_8(mmu::DIV_REG, Caller::CPU), 0);
+        assert_eq!(console.mmu.sysclock, 0);
+    }
+}
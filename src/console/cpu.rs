@@ -1,15 +1,21 @@
 use std::collections::HashSet;
-use crate::console::instruction::{Instruction};
-use crate::console::mmu::{Caller, Mmu};
+use crate::console::instruction::{InstructionInfo};
+use crate::console::bus::{Bus, M_CYCLE};
+use crate::console::mmu::Caller;
 use crate::console::cpu_registers::{CpuRegIndex, CpuRegisters};
 use crate::console::debugger::Debugger;
 use crate::console::interrupts::Interrupts;
 
+// Upper bound on how far a halted CPU skips ahead, so the main loop still gets to poll input
+const MAX_IDLE_CYCLES: u16 = 1024 * M_CYCLE;
+
 pub(crate) const PREFIX_BYTE: u8 = 0xCB;
 
 #[allow(dead_code)]
 pub(crate) struct Cpu {
     pub(crate) is_halted: bool,
+    pub(crate) is_stopped: bool,
+    pub(crate) lockup: Option<(u16, u16)>, // (address, opcode) of the illegal opcode that hung the CPU
     pub(crate) registers: CpuRegisters,
     pub(crate) interrupts: Interrupts,
     pub(crate) visited: HashSet<u16>,
@@ -20,6 +26,8 @@ impl Cpu {
     pub(crate) fn new(debug_print: bool) -> Cpu {
         Cpu {
             is_halted: false,
+            is_stopped: false,
+            lockup: None,
             registers: CpuRegisters::new(),
             interrupts: Interrupts::new(),
             visited: HashSet::from([]),
@@ -27,61 +35,108 @@ impl Cpu {
         }
     }
 
-    // TODO Broken?
-    pub(crate) fn handle_interrupts(&mut self, mmu: &mut Mmu) -> i16 {
-        let mut cycles = 0;
+    pub(crate) fn handle_interrupts(&mut self, bus: &mut Bus) -> i16 {
+        if self.lockup.is_some() || self.interrupts.peek_pending(bus.mmu) == 0 {
+            return 0;
+        }
+
+        // Any enabled and requested interrupt wakes the CPU from HALT, even if IME is off.
+        let was_halted = self.is_halted;
+        self.is_halted = false;
 
         if !self.interrupts.ime {
-            if self.is_halted && self.interrupts.peek_requested(mmu) {
-                self.is_halted = false;
-            }
-        } else {
-            let value = self.interrupts.poll(mmu);
-            if value > 0 {
-                if self.debug_print_on {
-                    println!("{:#06X}\t{} INTERRUPT! -- CALL {:#06X}",
-                        self.registers.get_word(CpuRegIndex::PC),
-                        match value {
-                            0x40 => "VBLANK",
-                            0x48 => "LCDSTAT",
-                            0x50 => "TIMER",
-                            0x58 => "SERIAL",
-                            0x60 => "JOYPAD",
-                            _ => "?",
-                        },
-                        value);
-                }
-                self.interrupts.ime = false;
-                Instruction::call(self, mmu, value as u16);
-                cycles = 20; // 2M wait + 2M push + 1M jump
-            }
+            return 0;
         }
-        cycles
+
+        let mut cycles = 0;
+        if was_halted {
+            bus.tick(M_CYCLE); // Exiting HALT takes an extra M-cycle before dispatch begins
+            cycles += M_CYCLE as i16;
+        }
+        cycles + self.dispatch_interrupt(bus)
     }
 
-    pub(crate) fn step(&mut self, mmu: &mut Mmu) -> i16 {
-        if self.is_halted {
-            4
-        } else {
-            self.execute_instruction(mmu)
+    // Dispatch takes 5 M-cycles: 2M wait + 2M push PC (high byte first) + 1M jump.
+    // The vector is only selected after the high byte of PC has been pushed, so if that push writes IE
+    // (SP wrapped to 0x0000) and disables the pending interrupt, dispatch is cancelled and PC is set to
+    // 0x0000 instead (mooneye acceptance/interrupts/ie_push).
+    fn dispatch_interrupt(&mut self, bus: &mut Bus) -> i16 {
+        self.interrupts.ime = false;
+        self.interrupts.ime_scheduled = false;
+
+        let pc = self.registers.get_word(CpuRegIndex::PC);
+
+        bus.tick(2 * M_CYCLE);
+
+        self.registers.decrement(CpuRegIndex::SP, 1);
+        bus.write_8(self.registers.get_word(CpuRegIndex::SP), (pc >> 8) as u8, Caller::CPU);
+
+        let value = self.interrupts.poll(bus.mmu);
+
+        self.registers.decrement(CpuRegIndex::SP, 1);
+        bus.write_8(self.registers.get_word(CpuRegIndex::SP), (pc & 0xFF) as u8, Caller::CPU);
+
+        bus.tick(M_CYCLE);
+
+        if self.debug_print_on {
+            println!("{:#06X}\t{} INTERRUPT! -- CALL {:#06X}",
+                pc,
+                match value {
+                    0x40 => "VBLANK",
+                    0x48 => "LCDSTAT",
+                    0x50 => "TIMER",
+                    0x58 => "SERIAL",
+                    0x60 => "JOYPAD",
+                    _ => "CANCELLED",
+                },
+                value);
         }
+
+        self.registers.set_word(CpuRegIndex::PC, value as u16);
+        20
     }
 
-    fn read_byte_at_pc(&mut self, mmu: &mut Mmu) -> u8 {
+    pub(crate) fn step(&mut self, bus: &mut Bus) -> i16 {
+        // Nothing to execute, wake up when the next event (which may request an interrupt) is due
+        if self.is_halted || self.lockup.is_some() {
+            return bus.tick_to_next_event(MAX_IDLE_CYCLES) as i16;
+        }
+
+        // An EI executed before this instruction takes effect once this instruction completes.
+        let enable_ime = self.interrupts.ime_scheduled;
+
+        // Memory accesses tick the bus as they happen, the remaining internal M-cycles are ticked afterward.
+        let start = bus.elapsed;
+        let cycles = self.execute_instruction(bus);
+        let ticked = bus.elapsed - start;
+        if cycles > ticked {
+            bus.tick((cycles - ticked) as u16);
+        }
+        let cycles = cycles.max(ticked); // STOP fetches its padding byte
+
+        if enable_ime && self.interrupts.ime_scheduled {
+            self.interrupts.ime = true;
+            self.interrupts.ime_scheduled = false;
+        }
+
+        cycles
+    }
+
+    fn read_byte_at_pc(&mut self, bus: &mut Bus) -> u8 {
         let pc = self.registers.get_word(CpuRegIndex::PC);
-        let d8 = mmu.read_8(pc, Caller::CPU);
+        let d8 = bus.read_8(pc, Caller::CPU);
         self.registers.increment(CpuRegIndex::PC, 1);
         d8
     }
 
-    fn fetch_opcode(&mut self, mmu: &mut Mmu) -> u16 {
+    fn fetch_opcode(&mut self, bus: &mut Bus) -> u16 {
         let mut opcode: u16;
 
-        let mut byte = self.read_byte_at_pc(mmu);
+        let mut byte = self.read_byte_at_pc(bus);
 
         if byte == PREFIX_BYTE {
             opcode = (byte as u16) << 8;
-            byte = self.read_byte_at_pc(mmu);
+            byte = self.read_byte_at_pc(bus);
             opcode |= byte as u16;
         } else {
             opcode = byte as u16;
@@ -90,41 +145,87 @@ impl Cpu {
         opcode
     }
 
-    fn fetch_args(&mut self, instruction: &Instruction, mmu: &mut Mmu) -> Vec<u8> {
-        let mut args: Vec<u8> = vec![];
-
-        if instruction.cycles < 0 {
-            return args;
-        }
+    // Reads up to 2 operand bytes into a fixed buffer, returns the number of bytes read.
+    fn fetch_args(&mut self, instruction: &InstructionInfo, bus: &mut Bus) -> ([u8; 2], usize) {
+        let mut args = [0u8; 2];
 
         let num_args = if instruction.is_cbprefixed() {
             instruction.size - 2
         } else {
             instruction.size - 1
-        };
+        } as usize;
 
-        if num_args > 0 {
-            args.push(self.read_byte_at_pc(mmu));
-        }
-        if num_args > 1 {
-            args.push(self.read_byte_at_pc(mmu));
+        for arg in args.iter_mut().take(num_args) {
+            *arg = self.read_byte_at_pc(bus);
         }
 
-        args
+        (args, num_args)
     }
 
-    fn execute_instruction(&mut self, mmu: &mut Mmu) -> i16 {
+    fn execute_instruction(&mut self, bus: &mut Bus) -> i16 {
         let start_pc = self.registers.get_word(CpuRegIndex::PC);
 
-        let opcode = self.fetch_opcode(mmu);
-        let mut instruction = Instruction::get_instruction(opcode);
-        let args = self.fetch_args(&instruction, mmu);
+        let opcode = self.fetch_opcode(bus);
+        let instruction = InstructionInfo::get_instruction(opcode);
+        let (args, num_args) = self.fetch_args(instruction, bus);
+        let args = &args[..num_args];
 
         if self.debug_print_on {
-            Debugger::print_cpu_exec(self, mmu, start_pc, opcode, instruction.mnemonic, args.as_slice());
+            Debugger::print_cpu_exec(self, bus.mmu, start_pc, opcode, instruction.mnemonic, args);
+        }
+
+        instruction.execute(self, bus, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+    use crate::console::mmu::Mmu;
+    use crate::console::ppu::Ppu;
+    use crate::console::scheduler::Scheduler;
+    use crate::console::serial::Serial;
+    use crate::console::timer::Timer;
+    use super::*;
+
+    // Run with: cargo test --release bench_cpu_step -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_cpu_step() {
+        // Tight loop in WRAM mixing immediates, ALU, (HL) access, a CB-prefixed op and branches.
+        let program: [u8; 15] = [
+            0x21, 0x00, 0xD0,   // C000: LD HL,$D000
+            0x3C,               // C003: INC A
+            0x80,               // C004: ADD A,B
+            0x77,               // C005: LD (HL),A
+            0xCB, 0x37,         // C006: SWAP A
+            0x46,               // C008: LD B,(HL)
+            0x0D,               // C009: DEC C
+            0x20, 0xF7,         // C00A: JR NZ,$C003
+            0xC3, 0x00, 0xC0,   // C00C: JP $C000
+        ];
+        let steps: u32 = 10_000_000;
+
+        let mut mmu = Mmu::new(None, true);
+        for (i, byte) in program.iter().enumerate() {
+            mmu.write_8(0xC000 + i as u16, *byte, Caller::CPU);
+        }
+        let mut ppu = Ppu::new();
+        let mut timer = Timer::new();
+        let mut serial = Serial::new();
+        let mut scheduler = Scheduler::new();
+        let mut bus = Bus::new(&mut mmu, &mut ppu, &mut timer, &mut serial, &mut scheduler);
+        let mut cpu = Cpu::new(false);
+        cpu.registers.set_word(CpuRegIndex::PC, 0xC000);
+
+        let start = Instant::now();
+        let mut cycles: u64 = 0;
+        for _ in 0..steps {
+            cycles += cpu.step(&mut bus) as u64;
         }
+        let elapsed = start.elapsed();
 
-        let args = args.as_ref();
-        instruction.execute(self, mmu, args)
+        println!("{} steps ({} cycles) in {:?}: {:.1} M steps/sec",
+            steps, cycles, elapsed, steps as f64 / elapsed.as_secs_f64() / 1_000_000.0);
     }
 }
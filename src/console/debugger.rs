@@ -39,6 +39,13 @@ impl Debugger {
         }
     }
 
+    pub(crate) fn force_break(&mut self, cpu: Option<&mut Cpu>, mmu: Option<&mut Mmu>, timer: Option<&mut Timer>, locals: Option<Vec<(&str, &str)>>) {
+        if self.enabled {
+            self.active = true;
+            self.dump(cpu, mmu, timer, locals);
+        }
+    }
+
     pub(crate) fn peek(&mut self, cpu: Option<&mut Cpu>, mmu: Option<&mut Mmu>, timer: Option<&mut Timer>, locals: Option<Vec<(&str, &str)>>) {
         if self.enabled {
             self.dump(cpu, mmu, timer, locals);
@@ -53,8 +60,8 @@ impl Debugger {
         for y in 0..ppu.lcd.height {
             print!("|");
             for x in 0..ppu.lcd.width {
-                let color = ppu.lcd.data[y][x];
-                match color % 4 {
+                let shade = ppu.lcd.data[y * ppu.lcd.width + x];
+                match shade {
                     3 => print!("@"),
                     2 => print!("+"),
                     1 => print!("."),
@@ -141,6 +148,10 @@ impl Debugger {
 
     fn dump_cpu_state(&self, cpu: &mut Cpu, mmu: &mut Mmu) {
         self.dump_key_value_pairs(vec![("Cpu.is_halted", format!("\t{}", cpu.is_halted).as_str())]);
+        self.dump_key_value_pairs(vec![("Cpu.is_stopped", format!("\t{}", cpu.is_stopped).as_str())]);
+        if let Some((address, opcode)) = cpu.lockup {
+            self.dump_key_value_pairs(vec![("Cpu.lockup", format!("\t{:#04X} at {:#06X}", opcode, address).as_str())]);
+        }
         self.dump_key_value_pairs(vec![("Cpu.registers", format!("\n{}", cpu.registers).as_str())]);
         self.dump_key_value_pairs(vec![("Cpu.interrupts", format!("\t{}", cpu.interrupts.as_str(mmu)).as_str())]);
     }
@@ -2,7 +2,7 @@ use std::fs;
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
-use crate::console::instruction::Instruction;
+use crate::console::instruction::InstructionInfo;
 
 const PREFIX_BYTE: u8 = 0xCB;
 
@@ -59,7 +59,7 @@ fn disassemble_opcode(mut pc: usize, rom: &Vec<u8>) -> (u16, usize) {
 fn disassemble_line(pc: usize, rom: &Vec<u8>) -> (String, usize) {
     let (opcode, mut new_pc) = disassemble_opcode(pc, rom);
 
-    let instruction = Instruction::get_instruction(opcode);
+    let instruction = InstructionInfo::get_instruction(opcode);
 
     let num_args = if instruction.is_cbprefixed() {
         if instruction.size > 1 { instruction.size - 2 } else { 0 }
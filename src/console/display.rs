@@ -1,167 +1,263 @@
-use sdl2::pixels::Color;
-use sdl2::rect::Rect;
-use sdl2::render::WindowCanvas;
+use std::collections::{HashSet, VecDeque};
+use std::path::Path;
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::render::{BlendMode, Texture, TextureCreator, WindowCanvas};
+use sdl2::video::{FullscreenType, WindowContext};
 use sdl2::Sdl;
-use crate::console::ppu::{Lcd, Ppu};
+use crate::console::framebuffer;
+use crate::console::framebuffer::{Palette, RGBA_SIZE};
+use crate::console::console::{CLOCK_HZ, CYCLES_PER_FRAME};
+use crate::console::gif_export;
+use crate::console::input::JoypadInput;
+use crate::console::lcd_filter::{FilterKind, LcdFilter};
+use crate::console::osd::Osd;
+use crate::console::palette_config::{PaletteConfig, PALETTE_CONFIG_PATH};
+use crate::console::ppu::{Layer, Lcd, Ppu, LCD_HEIGHT, LCD_PIXELS, LCD_WIDTH};
+use crate::console::recorder::RecordFormat;
+use crate::console::upscaler::{Upscaler, UpscalerKind};
 
 pub(crate) const WINDOW_SCALE: u32 = 5;
 
-const GRAY_LT: Color = Color::RGB(170, 170, 170);
-const GRAY_DK: Color = Color::RGB(85, 85, 85);
-const PINK_LT: Color = Color::RGB(255, 128, 128);
-const PINK_LT_MUTED: Color = Color::RGB(BLUE_LT.r / 4 * 3, BLUE_LT.g / 4 * 3, BLUE_LT.b);
-const BLUE_LT: Color = Color::RGB(64, 191, 255);
-const BLUE_LT_MUTED: Color = Color::RGB(PINK_LT.r, PINK_LT.g / 4 * 3, PINK_LT.b / 4 * 3);
-
-const PALETTE_BW: [Color; 4] = [
-    Color::WHITE,
-    GRAY_LT,
-    GRAY_DK,
-    Color::BLACK,
-];
-
-const PALETTE_CMYK: [Color; 4] = [
-    Color::CYAN,
-    Color::MAGENTA,
-    Color::YELLOW,
-    Color::BLACK,
-];
-
-const PALETTE_TRANS: [Color; 4] = [
-    Color::WHITE,
-    BLUE_LT,
-    PINK_LT,
-    Color::BLACK,
-];
-
-const PALETTE_TRANS_MUTED: [Color; 4] = [
-    Color::WHITE,
-    BLUE_LT_MUTED,
-    PINK_LT_MUTED,
-    Color::BLACK,
-];
-
-const PALETTE_DEBUG: [Color; 4] = [
-    Color::RED,
-    Color::GREEN,
-    Color::BLUE,
-    Color::WHITE,
-];
-
-fn create_sdl_canvas(sdl_context: &Sdl, window_width: u32, window_height: u32, window_title: &str) -> WindowCanvas {
+pub(crate) struct DisplayOptions {
+    pub(crate) window_scale: u32, // Initial window size, in multiples of the LCD resolution
+    pub(crate) vsync: bool,
+    pub(crate) integer_scale: bool,
+    pub(crate) is_headless: bool, // No window or input, e.g. to take a screenshot and exit
+    pub(crate) screenshot_scale: u32,
+    pub(crate) record_format: RecordFormat,
+    pub(crate) gif_seconds: u32, // Length of the rolling buffer saved as a GIF
+    pub(crate) upscaler: UpscalerKind,
+}
+
+impl DisplayOptions {
+    pub(crate) fn new() -> DisplayOptions {
+        DisplayOptions {
+            window_scale: WINDOW_SCALE,
+            vsync: false,
+            integer_scale: false,
+            is_headless: false,
+            screenshot_scale: 1,
+            record_format: RecordFormat::Y4m,
+            gif_seconds: 10,
+            upscaler: UpscalerKind::Nearest,
+        }
+    }
+}
+
+fn create_sdl_canvas(sdl_context: &Sdl, window_width: u32, window_height: u32, window_title: &str, vsync: bool) -> WindowCanvas {
     let video_subsystem = sdl_context.video().unwrap();
 
     let window = video_subsystem
         .window(window_title, window_width, window_height)
         .position_centered()
+        .resizable()
         .opengl()
         .build()
         .unwrap();
 
-    window.into_canvas()
+    let mut canvas_builder = window.into_canvas();
+    if vsync {
+        canvas_builder = canvas_builder.present_vsync();
+    }
+    canvas_builder
         .build()
         .unwrap()
 }
 
+// The frame is converted to RGBA and uploaded to a streaming texture once per draw,
+// the renderer scales it to the window. The window can be resized, the frame keeps its aspect
+// ratio with black bars around it (and only grows in whole multiples with integer scaling).
 pub(crate) struct Display {
-    selected_palette: usize,
-    palettes: [[Color; 16]; 4],
-    pixels: Vec<Vec<Rect>>,
+    palette_config: PaletteConfig,
+    rgba: Vec<u8>,
+    recent_frames: VecDeque<Vec<u8>>, // GIF color indices of the last presented frames, oldest first
+    recent_frames_capacity: usize,
+    lcd_filter: LcdFilter,
+    upscaler: Upscaler,
+    // Lives as long as the program, so the textures it creates can be kept next to the canvas
+    texture_creator: &'static TextureCreator<WindowContext>,
+    texture: Texture<'static>,
+    texture_size: (u32, u32), // Larger than the LCD when a filter or upscaler scales the frame up
+    osd: Osd,
+    osd_texture: Texture<'static>, // Blended over the frame
     canvas: WindowCanvas,
 }
 
 impl Display {
     pub(crate) fn new(
-            window_scale: u32,
+            options: &DisplayOptions,
             window_title: &str,
             sdl_context: &Sdl,
             lcd_pixel_width: usize,
             lcd_pixel_height: usize) -> Display {
-        let mut pixels = vec![vec![Rect::new(0,0,0,0); lcd_pixel_width]; lcd_pixel_height];
-        for y in 0..lcd_pixel_height as u32 {
-            for x in 0..lcd_pixel_width as u32 {
-                pixels[y as usize][x as usize] = Rect::new(
-                    (x * window_scale) as i32,
-                    (y * window_scale) as i32,
-                    window_scale,
-                    window_scale);
-            }
-        }
+        let mut canvas = create_sdl_canvas(
+            sdl_context,
+            lcd_pixel_width as u32 * options.window_scale,
+            lcd_pixel_height as u32 * options.window_scale,
+            window_title,
+            options.vsync);
+        canvas.set_logical_size(lcd_pixel_width as u32, lcd_pixel_height as u32).unwrap();
+        canvas.set_integer_scale(options.integer_scale).unwrap();
+
+        let texture_creator: &'static TextureCreator<WindowContext> = Box::leak(Box::new(canvas.texture_creator()));
+        let texture = texture_creator
+            .create_texture_streaming(PixelFormatEnum::RGBA32, lcd_pixel_width as u32, lcd_pixel_height as u32)
+            .unwrap();
+        let mut osd_texture = texture_creator
+            .create_texture_streaming(PixelFormatEnum::RGBA32, lcd_pixel_width as u32, lcd_pixel_height as u32)
+            .unwrap();
+        osd_texture.set_blend_mode(BlendMode::Blend);
+
+        let frame_duration = CYCLES_PER_FRAME as f64 / CLOCK_HZ as f64;
+        let recent_frames_capacity = (options.gif_seconds as f64 / frame_duration).ceil() as usize;
 
         Display {
-            selected_palette: 1,
-            palettes: [
-                Display::make_palette(PALETTE_BW, PALETTE_BW, PALETTE_BW, PALETTE_DEBUG),
-                Display::make_palette(PALETTE_BW, PALETTE_TRANS, PALETTE_TRANS_MUTED, PALETTE_DEBUG),
-                Display::make_palette(PALETTE_CMYK, PALETTE_CMYK, PALETTE_CMYK, PALETTE_DEBUG),
-                Display::generate_random_palette()
-            ],
-            canvas:
-                create_sdl_canvas(
-                    sdl_context,
-                    lcd_pixel_width as u32 * window_scale,
-                    lcd_pixel_height as u32 * window_scale,
-                    window_title
-                ),
-            pixels,
+            palette_config: PaletteConfig::load(PALETTE_CONFIG_PATH),
+            rgba: vec![0; RGBA_SIZE],
+            recent_frames: VecDeque::with_capacity(recent_frames_capacity),
+            recent_frames_capacity,
+            lcd_filter: LcdFilter::new(),
+            upscaler: Upscaler::new(options.upscaler),
+            texture_creator,
+            texture,
+            texture_size: (lcd_pixel_width as u32, lcd_pixel_height as u32),
+            osd: Osd::new(lcd_pixel_width, lcd_pixel_height),
+            osd_texture,
+            canvas,
         }
     }
 
-    pub(crate) fn draw(&mut self, ppu: &mut Ppu) {
-        self.canvas.clear();
+    pub(crate) fn draw(&mut self, ppu: &mut Ppu, active_input: &HashSet<JoypadInput>) {
+        framebuffer::write_rgba(&ppu.lcd, self.palette_config.active_palette(), &mut self.rgba);
+        self.buffer_frame(&ppu.lcd);
+
+        let (output_width, output_height) = self.canvas.output_size().unwrap();
+        let integer_scale = (output_width / ppu.lcd.width as u32).min(output_height / ppu.lcd.height as u32);
+        // The grid needs square pixels to draw its gaps on, so it is left out when upscaling
+        let grid_scale = if self.upscaler.kind() == UpscalerKind::Nearest {
+            self.lcd_filter.grid_scale(integer_scale)
+        } else {
+            1
+        };
+        let scale = grid_scale * self.upscaler.scale();
+        self.resize_texture(ppu.lcd.width as u32 * scale, ppu.lcd.height as u32 * scale);
 
-        self.draw_screen(&ppu.lcd);
+        let gap_color = self.palette_config.active_palette().colors[Layer::Background as usize][0];
+        let filtered = self.lcd_filter.apply(&self.rgba, ppu.lcd.width, ppu.lcd.height, grid_scale, gap_color);
+        let frame = self.upscaler.apply(filtered, ppu.lcd.width, ppu.lcd.height);
+        self.texture.update(None, frame, self.texture_size.0 as usize * 4).unwrap();
 
+        self.canvas.set_draw_color(Color::BLACK);
+        self.canvas.clear();
+        self.canvas.copy(&self.texture, None, None).unwrap();
+        if let Some(osd) = self.osd.render(active_input) {
+            self.osd_texture.update(None, osd, ppu.lcd.width * 4).unwrap();
+            self.canvas.copy(&self.osd_texture, None, None).unwrap();
+        }
         self.canvas.present();
-        self.canvas.set_draw_color(self.palettes[self.selected_palette][0]);
     }
 
-    pub(crate) fn cycle_color_palette(&mut self) {
-        self.selected_palette = (self.selected_palette + 1) % 4;
-        if self.selected_palette == 3 {
-            self.palettes[3] = Display::generate_random_palette();
+    // Prints `text`, and shows it on screen for a moment
+    pub(crate) fn notify(&mut self, text: &str) {
+        println!("{}", text);
+        self.osd.message(text);
+    }
+
+    pub(crate) fn toggle_filter(&mut self, kind: FilterKind) {
+        let is_enabled = self.lcd_filter.toggle(kind);
+        self.notify(&format!("LCD filter {:?}: {}", kind, if is_enabled { "on" } else { "off" }));
+    }
+
+    pub(crate) fn cycle_upscaler(&mut self) {
+        self.upscaler.cycle();
+        self.notify(&format!("Upscaler: {}", self.upscaler.kind().name()));
+    }
+
+    pub(crate) fn toggle_stats(&mut self) {
+        let is_enabled = self.osd.toggle_stats();
+        self.notify(&format!("FPS counter: {}", if is_enabled { "on" } else { "off" }));
+    }
+
+    pub(crate) fn toggle_input_display(&mut self) {
+        let is_enabled = self.osd.toggle_input();
+        self.notify(&format!("Input display: {}", if is_enabled { "on" } else { "off" }));
+    }
+
+    fn resize_texture(&mut self, width: u32, height: u32) {
+        if self.texture_size == (width, height) {
+            return;
         }
+
+        // The old texture is destroyed when it is dropped
+        self.texture = self.texture_creator
+            .create_texture_streaming(PixelFormatEnum::RGBA32, width, height)
+            .unwrap();
+        self.texture_size = (width, height);
     }
 
-    fn draw_scanline(&mut self, y: usize, scanline: Vec<Color>) {
-        for x in 0..scanline.len() {
-            let color = scanline[x];
-            self.canvas.set_draw_color(color);
-            self.canvas.fill_rect(self.pixels[y][x]).unwrap();
+    // Saves the buffered frames, with the active palette. Nothing is written without any.
+    pub(crate) fn save_gif(&self, path: &Path) -> Result<usize, String> {
+        if self.recent_frames.is_empty() {
+            return Err(String::from("no frames buffered"));
         }
+        gif_export::save_gif(
+            path,
+            self.recent_frames.iter().map(|frame| frame.as_slice()),
+            LCD_WIDTH,
+            LCD_HEIGHT,
+            self.active_palette(),
+            CYCLES_PER_FRAME as f64 / CLOCK_HZ as f64)
     }
 
-    fn draw_screen(&mut self, lcd: &Lcd) {
-        let height = self.pixels.len();
-        for y in 0..height {
-            let row = lcd.data[y].as_slice().iter();
-            let colors = row.map(|pixel| self.palettes[self.selected_palette][*pixel as usize]).collect();
-            self.draw_scanline(y, colors);
+    fn buffer_frame(&mut self, lcd: &Lcd) {
+        if self.recent_frames_capacity == 0 {
+            return;
+        }
+
+        // Reuse the oldest frame's buffer once full
+        let mut frame = if self.recent_frames.len() == self.recent_frames_capacity {
+            self.recent_frames.pop_front().unwrap()
+        } else {
+            vec![0; LCD_PIXELS]
+        };
+        match &lcd.layers {
+            Some(layers) => {
+                for ((index, &shade), &layer) in frame.iter_mut().zip(lcd.data.iter()).zip(layers.iter()) {
+                    *index = gif_export::color_index(shade, layer);
+                }
+            }
+            None => {
+                for (index, &shade) in frame.iter_mut().zip(lcd.data.iter()) {
+                    *index = gif_export::color_index(shade, Layer::Background as u8);
+                }
+            }
         }
+        self.recent_frames.push_back(frame);
+    }
+
+    // Last presented frame, as RGBA8888
+    pub(crate) fn rgba(&self) -> &[u8] {
+        &self.rgba
     }
 
-    fn make_palette(background_palette: [Color; 4], window_palette: [Color; 4], sprite_palette: [Color; 4], debug_palette: [Color; 4]) -> [Color; 16] {
-        let mut palette: [Color; 16] = [Color::CYAN; 16];
-        palette[..4].copy_from_slice(&background_palette);
-        palette[4..8].copy_from_slice(&window_palette);
-        palette[8..12].copy_from_slice(&sprite_palette);
-        palette[12..16].copy_from_slice(&debug_palette);
-        palette
+    pub(crate) fn active_palette(&self) -> &Palette {
+        self.palette_config.active_palette()
     }
 
-    fn generate_random_palette() -> [Color; 16] {
-        let mut palette: [Color; 16] = [Color::CYAN; 16];
-        for i in 0..12 {
-            palette[i] = Self::random_color();
+    pub(crate) fn toggle_fullscreen(&mut self) {
+        let window = self.canvas.window_mut();
+        let fullscreen_type = match window.fullscreen_state() {
+            FullscreenType::Off => FullscreenType::Desktop,
+            _ => FullscreenType::Off,
+        };
+        if let Err(error) = window.set_fullscreen(fullscreen_type) {
+            self.notify(&format!("WARNING: Failed to toggle fullscreen: {}", error));
         }
-        palette[12..16].copy_from_slice(&PALETTE_DEBUG);
-        palette
     }
 
-    fn random_color() -> Color {
-        let r: u8 = rand::random_range(0..=255);
-        let g: u8 = rand::random_range(0..=255);
-        let b: u8 = rand::random_range(0..=255);
-        Color::RGB(r, g, b)
+    pub(crate) fn cycle_color_palette(&mut self) {
+        self.palette_config.cycle(PALETTE_CONFIG_PATH);
+        let text = format!("Palette: {}", self.palette_config.palettes[self.palette_config.active].name);
+        self.notify(&text);
     }
 }
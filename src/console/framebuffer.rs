@@ -0,0 +1,99 @@
+use crate::console::ppu::{Layer, Lcd, LCD_PIXELS};
+
+// Converts the PPU's shade indices to RGBA8888, for the SDL display, screenshots and headless runs.
+
+pub(crate) type Rgba = [u8; 4];
+
+pub(crate) const RGBA_SIZE: usize = LCD_PIXELS * 4;
+
+const WHITE: Rgba = [255, 255, 255, 255];
+const GRAY_LT: Rgba = [170, 170, 170, 255];
+const GRAY_DK: Rgba = [85, 85, 85, 255];
+const BLACK: Rgba = [0, 0, 0, 255];
+const CYAN: Rgba = [0, 255, 255, 255];
+const MAGENTA: Rgba = [255, 0, 255, 255];
+const YELLOW: Rgba = [255, 255, 0, 255];
+const PINK_LT: Rgba = [255, 128, 128, 255];
+const PINK_LT_MUTED: Rgba = [BLUE_LT[0] / 4 * 3, BLUE_LT[1] / 4 * 3, BLUE_LT[2], 255];
+const BLUE_LT: Rgba = [64, 191, 255, 255];
+const BLUE_LT_MUTED: Rgba = [PINK_LT[0], PINK_LT[1] / 4 * 3, PINK_LT[2] / 4 * 3, 255];
+
+const SHADES_BW: [Rgba; 4] = [WHITE, GRAY_LT, GRAY_DK, BLACK];
+const SHADES_CMYK: [Rgba; 4] = [CYAN, MAGENTA, YELLOW, BLACK];
+const SHADES_TRANS: [Rgba; 4] = [WHITE, BLUE_LT, PINK_LT, BLACK];
+const SHADES_TRANS_MUTED: [Rgba; 4] = [WHITE, BLUE_LT_MUTED, PINK_LT_MUTED, BLACK];
+const SHADES_DMG_GREEN: [Rgba; 4] = [[155, 188, 15, 255], [139, 172, 15, 255], [48, 98, 48, 255], [15, 56, 15, 255]];
+const SHADES_POCKET_GRAY: [Rgba; 4] = [[196, 207, 161, 255], [139, 149, 109, 255], [77, 83, 60, 255], [31, 31, 31, 255]];
+
+pub(crate) const PALETTE_BW: Palette = Palette::new(SHADES_BW, SHADES_BW, SHADES_BW, SHADES_BW);
+pub(crate) const PALETTE_TRANS: Palette = Palette::new(SHADES_BW, SHADES_TRANS, SHADES_TRANS_MUTED, SHADES_TRANS_MUTED);
+pub(crate) const PALETTE_CMYK: Palette = Palette::new(SHADES_CMYK, SHADES_CMYK, SHADES_CMYK, SHADES_CMYK);
+pub(crate) const PALETTE_DMG_GREEN: Palette = Palette::new(SHADES_DMG_GREEN, SHADES_DMG_GREEN, SHADES_DMG_GREEN, SHADES_DMG_GREEN);
+pub(crate) const PALETTE_POCKET_GRAY: Palette = Palette::new(SHADES_POCKET_GRAY, SHADES_POCKET_GRAY, SHADES_POCKET_GRAY, SHADES_POCKET_GRAY);
+
+// Written to the palette config file the first time it is missing
+pub(crate) const PRESETS: [(&str, Palette); 5] = [
+    ("DMG Green", PALETTE_DMG_GREEN),
+    ("Pocket Gray", PALETTE_POCKET_GRAY),
+    ("Grayscale", PALETTE_BW),
+    ("Trans", PALETTE_TRANS),
+    ("CMYK", PALETTE_CMYK),
+];
+
+// Used when no palette was picked yet, or the config has none
+pub(crate) const DEFAULT_PALETTE: Palette = PALETTE_TRANS;
+pub(crate) const DEFAULT_PALETTE_NAME: &str = "Trans";
+
+// Colors for the 4 DMG shades, per layer (indexed by Layer)
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) struct Palette {
+    pub(crate) colors: [[Rgba; 4]; 4],
+}
+
+impl Palette {
+    pub(crate) const fn new(background: [Rgba; 4], window: [Rgba; 4], obj0: [Rgba; 4], obj1: [Rgba; 4]) -> Palette {
+        Palette {
+            colors: [background, window, obj0, obj1],
+        }
+    }
+
+    pub(crate) fn random() -> Palette {
+        let mut colors = [[BLACK; 4]; 4];
+        for shades in colors.iter_mut() {
+            for color in shades.iter_mut() {
+                *color = [rand::random(), rand::random(), rand::random(), 255];
+            }
+        }
+        Palette {
+            colors,
+        }
+    }
+
+    pub(crate) fn color(&self, shade: u8, layer: u8) -> Rgba {
+        self.colors[layer as usize][shade as usize]
+    }
+}
+
+// Fills `out` (RGBA_SIZE bytes, row-major) with the LCD's pixels. Without the layer plane,
+// every pixel uses the background colors.
+pub(crate) fn write_rgba(lcd: &Lcd, palette: &Palette, out: &mut [u8]) {
+    let out = &mut out[..RGBA_SIZE];
+    match &lcd.layers {
+        Some(layers) => {
+            for ((pixel, &shade), &layer) in out.chunks_exact_mut(4).zip(lcd.data.iter()).zip(layers.iter()) {
+                pixel.copy_from_slice(&palette.color(shade, layer));
+            }
+        }
+        None => {
+            for (pixel, &shade) in out.chunks_exact_mut(4).zip(lcd.data.iter()) {
+                pixel.copy_from_slice(&palette.color(shade, Layer::Background as u8));
+            }
+        }
+    }
+}
+
+pub(crate) fn to_rgba(lcd: &Lcd, palette: &Palette) -> Vec<u8> {
+    let mut out = vec![0; RGBA_SIZE];
+    write_rgba(lcd, palette, &mut out);
+    out
+}
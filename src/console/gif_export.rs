@@ -0,0 +1,55 @@
+use std::borrow::Cow;
+use std::fs;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use crate::console::framebuffer::Palette;
+
+pub(crate) const GIF_DIR: &str = "./gifs";
+
+// Pixels of a buffered frame index the GIF's global color table: layer * 4 + shade.
+// With the DMG's 4 shades per layer, that is at most 16 colors.
+pub(crate) fn color_index(shade: u8, layer: u8) -> u8 {
+    layer * 4 + shade
+}
+
+// Writes looping GIF frames shown every `frame_duration` seconds. GIF delays are in whole
+// centiseconds, so they are rounded per frame without letting the error add up.
+pub(crate) fn save_gif<'a>(
+        path: &Path,
+        frames: impl Iterator<Item = &'a [u8]>,
+        width: usize,
+        height: usize,
+        palette: &Palette,
+        frame_duration: f64) -> Result<usize, String> {
+    let mut color_table = Vec::with_capacity(16 * 3);
+    for shades in palette.colors.iter() {
+        for color in shades.iter() {
+            color_table.extend_from_slice(&color[..3]);
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    let mut encoder = gif::Encoder::new(BufWriter::new(file), width as u16, height as u16, &color_table)
+        .map_err(|e| e.to_string())?;
+    encoder.set_repeat(gif::Repeat::Infinite).map_err(|e| e.to_string())?;
+
+    let mut count = 0;
+    for pixels in frames {
+        let start = (count as f64 * frame_duration * 100.0).round() as u64;
+        let end = ((count + 1) as f64 * frame_duration * 100.0).round() as u64;
+        let frame = gif::Frame {
+            width: width as u16,
+            height: height as u16,
+            delay: (end - start) as u16,
+            buffer: Cow::Borrowed(pixels),
+            ..gif::Frame::default()
+        };
+        encoder.write_frame(&frame).map_err(|e| e.to_string())?;
+        count += 1;
+    }
+    Ok(count)
+}
@@ -0,0 +1,86 @@
+// Minimal INI files for the user config: `[section]` headers, `key = value` entries and `#` or `;`
+// comments. Entries before the first header belong to the unnamed section "".
+
+pub(crate) struct IniEntry {
+    pub(crate) key: String,
+    pub(crate) value: String,
+}
+
+pub(crate) struct IniSection {
+    pub(crate) name: String,
+    pub(crate) entries: Vec<IniEntry>,
+}
+
+impl IniSection {
+    pub(crate) fn get(&self, key: &str) -> Option<&str> {
+        self.entries.iter()
+            .find(|entry| entry.key.eq_ignore_ascii_case(key))
+            .map(|entry| entry.value.as_str())
+    }
+}
+
+pub(crate) struct Ini {
+    pub(crate) sections: Vec<IniSection>,
+}
+
+impl Ini {
+    // Lines that are neither a header nor an entry are reported and skipped.
+    pub(crate) fn parse(text: &str, file_name: &str) -> Ini {
+        let mut sections = vec![IniSection {
+            name: String::new(),
+            entries: vec![],
+        }];
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+                sections.push(IniSection {
+                    name: name.trim().to_string(),
+                    entries: vec![],
+                });
+            } else if let Some((key, value)) = line.split_once('=') {
+                sections.last_mut().unwrap().entries.push(IniEntry {
+                    key: key.trim().to_string(),
+                    value: value.trim().to_string(),
+                });
+            } else {
+                println!("WARNING: {}:{}: Expected '[section]' or 'key = value', ignoring '{}'.", file_name, i + 1, line);
+            }
+        }
+
+        Ini {
+            sections,
+        }
+    }
+}
+
+// Returns `text` with `key` in the unnamed section set to `value`, keeping every other line
+// (and comment) as it was.
+pub(crate) fn set_top_level_value(text: &str, key: &str, value: &str) -> String {
+    let mut lines: Vec<String> = text.lines().map(String::from).collect();
+    let new_line = format!("{} = {}", key, value);
+
+    let section_start = lines.iter()
+        .position(|line| line.trim().starts_with('['))
+        .unwrap_or(lines.len());
+    let existing = lines[..section_start].iter().position(|line| {
+        line.split_once('=').is_some_and(|(k, _)| k.trim().eq_ignore_ascii_case(key))
+    });
+
+    match existing {
+        Some(i) => lines[i] = new_line,
+        None => {
+            // Just before the first section
+            lines.insert(section_start, String::new());
+            lines.insert(section_start, new_line);
+        }
+    }
+
+    let mut new_text = lines.join("\n");
+    new_text.push('\n');
+    new_text
+}
@@ -1,10 +1,12 @@
-use sdl2::{EventPump, Sdl};
+use std::collections::{HashMap, HashSet};
+use sdl2::{EventPump, GameControllerSubsystem, Sdl};
+use sdl2::controller::{Axis, Button, GameController};
 use sdl2::event::Event;
-use sdl2::keyboard::Keycode;
+use crate::console::key_bindings::{KeyBindings, BINDINGS_CONFIG_PATH};
 use crate::console::mmu::Mmu;
 
 #[allow(dead_code)]
-#[derive(Debug, Eq, Hash, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub(crate) enum JoypadInput {
     InputKeyUp,
     InputKeyDown,
@@ -16,13 +18,23 @@ pub(crate) enum JoypadInput {
     InputKeyB,
 }
 
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub(crate) enum Callback {
     CycleColorPalette,
+    CycleUpscaler,
     DebugBreak,
     DebugPeek,
     DebugPrintScreen,
     Exit,
+    SaveGif,
+    Screenshot,
+    ToggleColorResponse,
+    ToggleFullscreen,
+    ToggleGhosting,
+    ToggleGrid,
+    ToggleInputDisplay,
+    ToggleRecording,
+    ToggleStats,
     InputKeyUp,
     InputKeyDown,
     InputKeyLeft,
@@ -33,96 +45,214 @@ pub(crate) enum Callback {
     InputKeyB,
 }
 
+struct Controller {
+    controller: GameController, // Closed when dropped
+    held_buttons: HashSet<Button>,
+    stick: (i8, i8), // Direction the left stick is pushed in on each axis: -1, 0 or 1
+}
+
 pub(crate) struct Input {
     event_pump: EventPump,
+    controller_subsystem: Option<GameControllerSubsystem>, // None when SDL can't provide it
+    controllers: HashMap<u32, Controller>, // By joystick instance id
+    key_bindings: KeyBindings,
+    // How many keys, buttons and stick directions hold each joypad input. The keyboard and every
+    // controller hold inputs on their own, an input is only let go once none of them holds it.
+    held_inputs: HashMap<JoypadInput, u8>,
 }
 
 impl Input {
     pub(crate) fn new(sdl_context: &mut Sdl) -> Input {
+        // Controllers that are already plugged in are reported by ControllerDeviceAdded events too
+        let controller_subsystem = match sdl_context.game_controller() {
+            Ok(subsystem) => Option::from(subsystem),
+            Err(error) => {
+                println!("WARNING: Game controllers are unavailable: {}", error);
+                None
+            }
+        };
+
         Input {
             event_pump: sdl_context.event_pump().unwrap(),
+            controller_subsystem,
+            controllers: HashMap::new(),
+            key_bindings: KeyBindings::load(BINDINGS_CONFIG_PATH),
+            held_inputs: HashMap::new(),
         }
     }
 
     pub(crate) fn poll(&mut self, mmu: &mut Mmu) -> Vec<Callback> {
         let mut callbacks: Vec<Callback> = vec![];
-        for event in self.event_pump.poll_iter() {
+        let events: Vec<Event> = self.event_pump.poll_iter().collect();
+        for event in events {
             match event {
-                Event::Quit { .. }
-                | Event::KeyDown { keycode: Some(Keycode::Escape), .. } => {
+                Event::Quit { .. } => {
                     callbacks.push(Callback::Exit);
                 }
-                Event::KeyDown { keycode: Some(Keycode::B), .. } => {
-                    callbacks.push(Callback::DebugBreak);
-                }
-                Event::KeyDown { keycode: Some(Keycode::P), .. } => {
-                    callbacks.push(Callback::DebugPeek);
-                }
-                Event::KeyDown { keycode: Some(Keycode::O), .. } => {
-                    callbacks.push(Callback::DebugPrintScreen);
-                }
-                Event::KeyDown { keycode: Some(Keycode::C), .. } => {
-                    callbacks.push(Callback::CycleColorPalette);
-                }
-                Event::KeyDown { keycode: Some(Keycode::Down), .. } => {
-                    callbacks.push(Callback::InputKeyDown);
-                    mmu.active_input.insert(JoypadInput::InputKeyDown);
-                }
-                Event::KeyUp { keycode: Some(Keycode::Down), .. } => {
-                    mmu.active_input.remove(&JoypadInput::InputKeyDown);
-                }
-                Event::KeyDown { keycode: Some(Keycode::Up), .. } => {
-                    callbacks.push(Callback::InputKeyUp);
-                    mmu.active_input.insert(JoypadInput::InputKeyUp);
-                }
-                Event::KeyUp { keycode: Some(Keycode::Up), .. } => {
-                    mmu.active_input.remove(&JoypadInput::InputKeyUp);
+                Event::KeyDown { keycode: Some(keycode), repeat, .. } => {
+                    if let Some(callback) = self.key_bindings.callback(keycode) {
+                        // A held key repeats, but only holds its joypad input once
+                        if !repeat {
+                            self.press(callback, mmu, &mut callbacks);
+                        } else if Input::joypad_input(callback).is_none() {
+                            callbacks.push(callback);
+                        }
+                    }
                 }
-                Event::KeyDown { keycode: Some(Keycode::Left), .. } => {
-                    callbacks.push(Callback::InputKeyLeft);
-                    mmu.active_input.insert(JoypadInput::InputKeyLeft);
+                Event::KeyUp { keycode: Some(keycode), .. } => {
+                    if let Some(callback) = self.key_bindings.callback(keycode) {
+                        self.release(callback, mmu);
+                    }
                 }
-                Event::KeyUp { keycode: Some(Keycode::Left), .. } => {
-                    mmu.active_input.remove(&JoypadInput::InputKeyLeft);
+                Event::ControllerDeviceAdded { which, .. } => {
+                    self.open_controller(which);
                 }
-                Event::KeyDown { keycode: Some(Keycode::Right), .. } => {
-                    callbacks.push(Callback::InputKeyRight);
-                    mmu.active_input.insert(JoypadInput::InputKeyRight);
+                Event::ControllerDeviceRemoved { which, .. } => {
+                    self.close_controller(which, mmu);
                 }
-                Event::KeyUp { keycode: Some(Keycode::Right), .. } => {
-                    mmu.active_input.remove(&JoypadInput::InputKeyRight);
+                Event::ControllerButtonDown { which, button, .. } => {
+                    let is_new = match self.controllers.get_mut(&which) {
+                        Some(controller) => controller.held_buttons.insert(button),
+                        None => false,
+                    };
+                    if let (true, Some(callback)) = (is_new, self.key_bindings.button_callback(button)) {
+                        self.press(callback, mmu, &mut callbacks);
+                    }
                 }
-                Event::KeyDown { keycode: Some(Keycode::A), .. } => {
-                    callbacks.push(Callback::InputKeySelect);
-                    mmu.active_input.insert(JoypadInput::InputKeySelect);
+                Event::ControllerButtonUp { which, button, .. } => {
+                    let was_held = match self.controllers.get_mut(&which) {
+                        Some(controller) => controller.held_buttons.remove(&button),
+                        None => false,
+                    };
+                    if let (true, Some(callback)) = (was_held, self.key_bindings.button_callback(button)) {
+                        self.release(callback, mmu);
+                    }
                 }
-                Event::KeyUp { keycode: Some(Keycode::A), .. } => {
-                    mmu.active_input.remove(&JoypadInput::InputKeySelect);
+                Event::ControllerAxisMotion { which, axis: axis @ (Axis::LeftX | Axis::LeftY), value, .. } => {
+                    self.move_stick(which, axis, value, mmu, &mut callbacks);
                 }
-                Event::KeyDown { keycode: Some(Keycode::S), .. } => {
-                    callbacks.push(Callback::InputKeyStart);
-                    mmu.active_input.insert(JoypadInput::InputKeyStart);
-                }
-                Event::KeyUp { keycode: Some(Keycode::S), .. } => {
-                    mmu.active_input.remove(&JoypadInput::InputKeyStart);
-                }
-                Event::KeyDown { keycode: Some(Keycode::Z), .. } => {
-                    callbacks.push(Callback::InputKeyB);
-                    mmu.active_input.insert(JoypadInput::InputKeyB);
+                _ => { }
+            }
+        }
+        callbacks
+    }
+
+    // `joystick_index` is the device index from ControllerDeviceAdded
+    fn open_controller(&mut self, joystick_index: u32) {
+        let subsystem = match self.controller_subsystem {
+            Some(ref subsystem) => subsystem,
+            None => return,
+        };
+        match subsystem.open(joystick_index) {
+            Ok(controller) => {
+                println!("Controller connected: {}", controller.name());
+                self.controllers.insert(controller.instance_id(), Controller {
+                    controller,
+                    held_buttons: HashSet::new(),
+                    stick: (0, 0),
+                });
+            }
+            Err(error) => println!("WARNING: Failed to open controller {}: {}", joystick_index, error),
+        }
+    }
+
+    // Lets go of everything the controller was holding
+    fn close_controller(&mut self, instance_id: u32, mmu: &mut Mmu) {
+        if let Some(controller) = self.controllers.remove(&instance_id) {
+            println!("Controller disconnected: {}", controller.controller.name());
+            for button in controller.held_buttons {
+                if let Some(callback) = self.key_bindings.button_callback(button) {
+                    self.release(callback, mmu);
                 }
-                Event::KeyUp { keycode: Some(Keycode::Z), .. } => {
-                    mmu.active_input.remove(&JoypadInput::InputKeyB);
+            }
+            for (direction, negative, positive) in [
+                    (controller.stick.0, Callback::InputKeyLeft, Callback::InputKeyRight),
+                    (controller.stick.1, Callback::InputKeyUp, Callback::InputKeyDown)] {
+                match direction {
+                    -1 => self.release(negative, mmu),
+                    1 => self.release(positive, mmu),
+                    _ => { }
                 }
-                Event::KeyDown { keycode: Some(Keycode::X), .. } => {
-                    callbacks.push(Callback::InputKeyA);
-                    mmu.active_input.insert(JoypadInput::InputKeyA);
+            }
+        }
+    }
+
+    // The left stick works as the D-pad once pushed past the deadzone. Only changes of direction
+    // press or release, so the keyboard and D-pad still work while the stick rests.
+    fn move_stick(&mut self, instance_id: u32, axis: Axis, value: i16, mmu: &mut Mmu, callbacks: &mut Vec<Callback>) {
+        let deadzone = self.key_bindings.stick_deadzone;
+        let controller = match self.controllers.get_mut(&instance_id) {
+            Some(controller) => controller,
+            None => return,
+        };
+
+        let direction = if value < -deadzone {
+            -1
+        } else if value > deadzone {
+            1
+        } else {
+            0
+        };
+        let (held, negative, positive) = match axis {
+            Axis::LeftX => (&mut controller.stick.0, Callback::InputKeyLeft, Callback::InputKeyRight),
+            _ => (&mut controller.stick.1, Callback::InputKeyUp, Callback::InputKeyDown),
+        };
+        if *held == direction {
+            return;
+        }
+        let previous = std::mem::replace(held, direction);
+
+        match previous {
+            -1 => self.release(negative, mmu),
+            1 => self.release(positive, mmu),
+            _ => { }
+        }
+        match direction {
+            -1 => self.press(negative, mmu, callbacks),
+            1 => self.press(positive, mmu, callbacks),
+            _ => { }
+        }
+    }
+
+    // Joypad inputs only go down (and request the interrupt) when nothing else holds them yet
+    fn press(&mut self, callback: Callback, mmu: &mut Mmu, callbacks: &mut Vec<Callback>) {
+        match Input::joypad_input(callback) {
+            Some(joypad_input) => {
+                let count = self.held_inputs.entry(joypad_input).or_insert(0);
+                *count += 1;
+                if *count == 1 {
+                    mmu.active_input.insert(joypad_input);
+                    callbacks.push(callback);
                 }
-                Event::KeyUp { keycode: Some(Keycode::X), .. } => {
-                    mmu.active_input.remove(&JoypadInput::InputKeyA);
+            }
+            None => callbacks.push(callback),
+        }
+    }
+
+    // Joypad inputs only go up once nothing holds them anymore
+    fn release(&mut self, callback: Callback, mmu: &mut Mmu) {
+        if let Some(joypad_input) = Input::joypad_input(callback) {
+            if let Some(count) = self.held_inputs.get_mut(&joypad_input) {
+                *count -= 1;
+                if *count == 0 {
+                    self.held_inputs.remove(&joypad_input);
+                    mmu.active_input.remove(&joypad_input);
                 }
-                _ => { }
             }
         }
-        callbacks
+    }
+
+    fn joypad_input(callback: Callback) -> Option<JoypadInput> {
+        match callback {
+            Callback::InputKeyUp => Option::from(JoypadInput::InputKeyUp),
+            Callback::InputKeyDown => Option::from(JoypadInput::InputKeyDown),
+            Callback::InputKeyLeft => Option::from(JoypadInput::InputKeyLeft),
+            Callback::InputKeyRight => Option::from(JoypadInput::InputKeyRight),
+            Callback::InputKeyStart => Option::from(JoypadInput::InputKeyStart),
+            Callback::InputKeySelect => Option::from(JoypadInput::InputKeySelect),
+            Callback::InputKeyA => Option::from(JoypadInput::InputKeyA),
+            Callback::InputKeyB => Option::from(JoypadInput::InputKeyB),
+            _ => None,
+        }
     }
 }
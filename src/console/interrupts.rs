@@ -30,12 +30,15 @@ pub(crate) struct Interrupts {
     pub(crate) enabled: Register,
     pub(crate) requested: Register,
     pub(crate) ime: bool,
+    // EI sets IME only after the instruction following it has executed (DI cancels a scheduled enable).
+    pub(crate) ime_scheduled: bool,
 }
 
 impl Interrupts {
     pub(crate) fn new() -> Interrupts {
         Interrupts {
             ime: false,
+            ime_scheduled: false,
             enabled: Register::new(mmu::IE_REG),
             requested: Register::new(mmu::IF_REG),
         }
@@ -45,20 +48,14 @@ impl Interrupts {
         self.requested.set_bit(mmu, requesting as u8, true, Caller::CPU);
     }
 
-    pub(crate) fn peek_requested(&mut self, mmu: &mut Mmu) -> bool {
-        self.requested.read(mmu, Caller::CPU) != 0
-    }
-
-    pub(crate) fn peek_interrupts(&mut self, mmu: &mut Mmu) -> u8 {
-        if self.ime {
-            self.enabled.read(mmu, Caller::CPU) & self.requested.read(mmu, Caller::CPU)
-        } else {
-            0
-        }
+    // Interrupts that are both enabled and requested, regardless of IME (used to wake from HALT).
+    pub(crate) fn peek_pending(&mut self, mmu: &mut Mmu) -> u8 {
+        self.enabled.read(mmu, Caller::CPU) & self.requested.read(mmu, Caller::CPU) & 0x1F
     }
 
+    // Does not check IME: the CPU has already cleared it by the time the vector is selected during dispatch.
     pub(crate) fn poll(&mut self, mmu: &mut Mmu) -> u8 {
-        let interrupt = self.peek_interrupts(mmu);
+        let interrupt = self.peek_pending(mmu);
 
         // Acknowledge (flip requested bit back to 0) and return call address
         if interrupt & (1 << InterruptRegBit::VBlank as u8) == (1 << InterruptRegBit::VBlank as u8) {
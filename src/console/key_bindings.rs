@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::fs;
+use std::hash::Hash;
+use std::path::Path;
+use sdl2::controller::Button;
+use sdl2::keyboard::Keycode;
+use crate::console::ini::Ini;
+use crate::console::input::Callback;
+
+// Keyboard and game controller bindings, one entry per joypad button or emulator action:
+//
+//   [Joypad]
+//   a = X, Space
+//   ...
+//   [Emulator]
+//   screenshot = F12
+//   ...
+//   [Controller]
+//   stick_deadzone = 8000
+//   a = b
+//   screenshot = rightshoulder
+//   ...
+//
+// Keys are SDL key names (https://wiki.libsdl.org/SDL2/SDL_Keycode) and buttons SDL GameController
+// button names, separated by commas. [Controller] takes the actions of both other sections. An
+// empty value leaves the action unbound, a missing one keeps its defaults. A key or button can
+// only be bound to one action, the first binding is kept.
+
+pub(crate) const BINDINGS_CONFIG_PATH: &str = "./config/bindings.ini";
+
+const JOYPAD_SECTION: &str = "Joypad";
+const EMULATOR_SECTION: &str = "Emulator";
+const CONTROLLER_SECTION: &str = "Controller";
+
+// How far the analog stick has to be pushed (out of 32767) to press a direction
+const STICK_DEADZONE_KEY: &str = "stick_deadzone";
+const STICK_DEADZONE: i16 = 8000;
+
+const BUTTON_NAMES: &str = "a, b, x, y, back, guide, start, leftstick, rightstick, leftshoulder, rightshoulder, \
+    dpup, dpdown, dpleft, dpright";
+
+struct Binding {
+    section: &'static str,
+    name: &'static str,
+    callback: Callback,
+    default_keys: &'static [&'static str],
+    default_buttons: &'static [&'static str], // Xbox layout positions, so the GB's B and A sit like on a GB
+}
+
+const BINDINGS: [Binding; 23] = [
+    Binding { section: JOYPAD_SECTION, name: "up", callback: Callback::InputKeyUp, default_keys: &["Up"], default_buttons: &["dpup"] },
+    Binding { section: JOYPAD_SECTION, name: "down", callback: Callback::InputKeyDown, default_keys: &["Down"], default_buttons: &["dpdown"] },
+    Binding { section: JOYPAD_SECTION, name: "left", callback: Callback::InputKeyLeft, default_keys: &["Left"], default_buttons: &["dpleft"] },
+    Binding { section: JOYPAD_SECTION, name: "right", callback: Callback::InputKeyRight, default_keys: &["Right"], default_buttons: &["dpright"] },
+    Binding { section: JOYPAD_SECTION, name: "a", callback: Callback::InputKeyA, default_keys: &["X"], default_buttons: &["b"] },
+    Binding { section: JOYPAD_SECTION, name: "b", callback: Callback::InputKeyB, default_keys: &["Z"], default_buttons: &["a"] },
+    Binding { section: JOYPAD_SECTION, name: "start", callback: Callback::InputKeyStart, default_keys: &["S"], default_buttons: &["start"] },
+    Binding { section: JOYPAD_SECTION, name: "select", callback: Callback::InputKeySelect, default_keys: &["A"], default_buttons: &["back"] },
+    Binding { section: EMULATOR_SECTION, name: "exit", callback: Callback::Exit, default_keys: &["Escape"], default_buttons: &[] },
+    Binding { section: EMULATOR_SECTION, name: "cycle_color_palette", callback: Callback::CycleColorPalette, default_keys: &["C"], default_buttons: &[] },
+    Binding { section: EMULATOR_SECTION, name: "toggle_stats", callback: Callback::ToggleStats, default_keys: &["F3"], default_buttons: &[] },
+    Binding { section: EMULATOR_SECTION, name: "toggle_input_display", callback: Callback::ToggleInputDisplay, default_keys: &["F4"], default_buttons: &[] },
+    Binding { section: EMULATOR_SECTION, name: "toggle_ghosting", callback: Callback::ToggleGhosting, default_keys: &["F5"], default_buttons: &[] },
+    Binding { section: EMULATOR_SECTION, name: "toggle_grid", callback: Callback::ToggleGrid, default_keys: &["F6"], default_buttons: &[] },
+    Binding { section: EMULATOR_SECTION, name: "toggle_color_response", callback: Callback::ToggleColorResponse, default_keys: &["F7"], default_buttons: &[] },
+    Binding { section: EMULATOR_SECTION, name: "cycle_upscaler", callback: Callback::CycleUpscaler, default_keys: &["F8"], default_buttons: &[] },
+    Binding { section: EMULATOR_SECTION, name: "save_gif", callback: Callback::SaveGif, default_keys: &["F9"], default_buttons: &[] },
+    Binding { section: EMULATOR_SECTION, name: "toggle_recording", callback: Callback::ToggleRecording, default_keys: &["F10"], default_buttons: &[] },
+    Binding { section: EMULATOR_SECTION, name: "toggle_fullscreen", callback: Callback::ToggleFullscreen, default_keys: &["F11"], default_buttons: &[] },
+    Binding { section: EMULATOR_SECTION, name: "screenshot", callback: Callback::Screenshot, default_keys: &["F12"], default_buttons: &[] },
+    Binding { section: EMULATOR_SECTION, name: "debug_break", callback: Callback::DebugBreak, default_keys: &["B"], default_buttons: &[] },
+    Binding { section: EMULATOR_SECTION, name: "debug_peek", callback: Callback::DebugPeek, default_keys: &["P"], default_buttons: &[] },
+    Binding { section: EMULATOR_SECTION, name: "debug_print_screen", callback: Callback::DebugPrintScreen, default_keys: &["O"], default_buttons: &[] },
+];
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum InputKind {
+    Key,
+    Button,
+}
+
+impl InputKind {
+    // Where a binding's keys or buttons are read from
+    fn section(&self, binding: &Binding) -> &'static str {
+        match self {
+            InputKind::Key => binding.section,
+            InputKind::Button => CONTROLLER_SECTION,
+        }
+    }
+
+    fn defaults(&self, binding: &Binding) -> &'static [&'static str] {
+        match self {
+            InputKind::Key => binding.default_keys,
+            InputKind::Button => binding.default_buttons,
+        }
+    }
+
+    fn unknown_message(&self, name: &str) -> String {
+        match self {
+            InputKind::Key => format!("unknown key '{}', see https://wiki.libsdl.org/SDL2/SDL_Keycode for names", name),
+            InputKind::Button => format!("unknown button '{}', expected one of {}", name, BUTTON_NAMES),
+        }
+    }
+}
+
+pub(crate) struct KeyBindings {
+    callbacks: HashMap<Keycode, Callback>,
+    button_callbacks: HashMap<Button, Callback>,
+    pub(crate) stick_deadzone: i16,
+}
+
+impl KeyBindings {
+    // Writes the defaults to `path` if it doesn't exist yet. Invalid and duplicate bindings are
+    // reported and skipped.
+    pub(crate) fn load(path: &str) -> KeyBindings {
+        if !Path::new(path).exists() {
+            KeyBindings::write_defaults(path);
+        }
+
+        let ini = match fs::read_to_string(path) {
+            Ok(text) => Ini::parse(&text, path),
+            Err(error) => {
+                println!("WARNING: Failed to read bindings '{}': {}, using the defaults.", path, error);
+                Ini::parse("", path)
+            }
+        };
+        KeyBindings::warn_unknown_entries(&ini, path);
+
+        let stick_deadzone = match KeyBindings::get(&ini, CONTROLLER_SECTION, STICK_DEADZONE_KEY) {
+            Some(value) => value.parse::<i16>().ok().filter(|deadzone| *deadzone >= 0).unwrap_or_else(|| {
+                println!("WARNING: {}: [{}] '{}' expects a number from 0 to 32767, using {}.",
+                    path, CONTROLLER_SECTION, STICK_DEADZONE_KEY, STICK_DEADZONE);
+                STICK_DEADZONE
+            }),
+            None => STICK_DEADZONE,
+        };
+
+        KeyBindings {
+            callbacks: KeyBindings::load_inputs(&ini, path, InputKind::Key, Keycode::from_name),
+            button_callbacks: KeyBindings::load_inputs(&ini, path, InputKind::Button, Button::from_string),
+            stick_deadzone,
+        }
+    }
+
+    pub(crate) fn callback(&self, keycode: Keycode) -> Option<Callback> {
+        self.callbacks.get(&keycode).copied()
+    }
+
+    pub(crate) fn button_callback(&self, button: Button) -> Option<Callback> {
+        self.button_callbacks.get(&button).copied()
+    }
+
+    fn load_inputs<T: Copy + Eq + Hash>(ini: &Ini, path: &str, kind: InputKind, parse: fn(&str) -> Option<T>) -> HashMap<T, Callback> {
+        let mut callbacks = HashMap::new();
+        let mut bound_by: HashMap<T, &str> = HashMap::new();
+        for binding in BINDINGS.iter() {
+            let section = kind.section(binding);
+            let names: Vec<&str> = match KeyBindings::get(ini, section, binding.name) {
+                Some(value) => value.split(',').map(str::trim).filter(|name| !name.is_empty()).collect(),
+                None => kind.defaults(binding).to_vec(),
+            };
+
+            for name in names {
+                let input = match parse(name) {
+                    Some(input) => input,
+                    None => {
+                        println!("WARNING: {}: [{}] '{}': {}.", path, section, binding.name, kind.unknown_message(name));
+                        continue;
+                    }
+                };
+                match bound_by.get(&input) {
+                    Some(&other) if other == binding.name => {}
+                    Some(&other) => {
+                        println!("WARNING: {}: [{}] '{}' is bound to both '{}' and '{}', keeping '{}'.",
+                            path, section, name, other, binding.name, other);
+                    }
+                    None => {
+                        bound_by.insert(input, binding.name);
+                        callbacks.insert(input, binding.callback);
+                    }
+                }
+            }
+        }
+        callbacks
+    }
+
+    fn get<'a>(ini: &'a Ini, section: &str, key: &str) -> Option<&'a str> {
+        ini.sections.iter()
+            .filter(|ini_section| ini_section.name.eq_ignore_ascii_case(section))
+            .find_map(|ini_section| ini_section.get(key))
+    }
+
+    fn warn_unknown_entries(ini: &Ini, path: &str) {
+        for section in ini.sections.iter() {
+            let is_controller = section.name.eq_ignore_ascii_case(CONTROLLER_SECTION);
+            if section.name.is_empty() {
+                for entry in section.entries.iter() {
+                    println!("WARNING: {}: '{}' is outside of [{}], [{}] and [{}], ignoring it.",
+                        path, entry.key, JOYPAD_SECTION, EMULATOR_SECTION, CONTROLLER_SECTION);
+                }
+            } else if !is_controller && !BINDINGS.iter().any(|binding| binding.section.eq_ignore_ascii_case(&section.name)) {
+                println!("WARNING: {}: Unknown section [{}], ignoring it.", path, section.name);
+            } else {
+                for entry in section.entries.iter() {
+                    let is_known = BINDINGS.iter().any(|binding| {
+                        (is_controller || binding.section.eq_ignore_ascii_case(&section.name))
+                            && binding.name.eq_ignore_ascii_case(&entry.key)
+                    }) || (is_controller && entry.key.eq_ignore_ascii_case(STICK_DEADZONE_KEY));
+                    if !is_known {
+                        println!("WARNING: {}: [{}] Unknown action '{}', ignoring it.", path, section.name, entry.key);
+                    }
+                }
+            }
+        }
+    }
+
+    fn write_defaults(path: &str) {
+        let mut text = String::from("# GamBoy key and game controller bindings.\n\
+            # Keys are SDL key names (https://wiki.libsdl.org/SDL2/SDL_Keycode), separated by commas.\n\
+            # Leave a value empty to unbind the action. Each key or button can only be bound once.\n");
+        for section in [JOYPAD_SECTION, EMULATOR_SECTION] {
+            text.push_str(&format!("\n[{}]\n", section));
+            for binding in BINDINGS.iter().filter(|binding| binding.section == section) {
+                text.push_str(&format!("{} = {}\n", binding.name, binding.default_keys.join(", ")));
+            }
+        }
+
+        text.push_str(&format!("\n[{}]\n\
+            # Buttons by their position on an Xbox style controller: {}.\n\
+            # Any [{}] or [{}] action can be bound here too.\n\
+            # The left stick presses the directions once pushed past {} (0 to 32767).\n",
+            CONTROLLER_SECTION, BUTTON_NAMES, JOYPAD_SECTION, EMULATOR_SECTION, STICK_DEADZONE_KEY));
+        text.push_str(&format!("{} = {}\n", STICK_DEADZONE_KEY, STICK_DEADZONE));
+        for binding in BINDINGS.iter().filter(|binding| !binding.default_buttons.is_empty()) {
+            text.push_str(&format!("{} = {}\n", binding.name, binding.default_buttons.join(", ")));
+        }
+
+        let result = Path::new(path).parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(path, text));
+        match result {
+            Ok(()) => println!("Wrote default bindings to '{}'.", path),
+            Err(error) => println!("WARNING: Failed to write default bindings to '{}': {}", path, error),
+        }
+    }
+}
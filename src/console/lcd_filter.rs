@@ -0,0 +1,136 @@
+use crate::console::framebuffer::Rgba;
+
+// Post-processing that imitates the DMG's LCD, only applied to what is shown in the window
+// (screenshots and recordings stay clean).
+//  - Ghosting: the LCD is slow to change, so each frame is blended with the previous ones. Games
+//    that flicker sprites every other frame rely on this to look transparent instead of flashing.
+//  - Grid: the gaps between the LCD's dots, drawn when the frame is shown at an integer scale >= 2.
+//  - Color response: the LCD never gets fully dark or fully light, and its response isn't linear.
+
+// How much of the previous frame is left in the next one, in 1/256ths
+const GHOSTING_PERSISTENCE: u16 = 128;
+// Darkest and lightest levels the LCD reaches, and the curve in between
+const RESPONSE_MIN: f64 = 24.0;
+const RESPONSE_MAX: f64 = 232.0;
+const RESPONSE_GAMMA: f64 = 1.25;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) enum FilterKind {
+    Ghosting,
+    Grid,
+    ColorResponse,
+}
+
+pub(crate) struct LcdFilter {
+    is_ghosting: bool,
+    is_grid: bool,
+    is_color_response: bool,
+    response_curve: [u8; 256],
+    previous: Vec<u8>, // Last ghosted frame, empty when there is none yet
+    frame: Vec<u8>,
+    scaled: Vec<u8>,
+}
+
+impl LcdFilter {
+    pub(crate) fn new() -> LcdFilter {
+        let mut response_curve = [0; 256];
+        for (i, level) in response_curve.iter_mut().enumerate() {
+            let x = i as f64 / 255.0;
+            *level = (RESPONSE_MIN + x.powf(RESPONSE_GAMMA) * (RESPONSE_MAX - RESPONSE_MIN)).round() as u8;
+        }
+
+        LcdFilter {
+            is_ghosting: false,
+            is_grid: false,
+            is_color_response: false,
+            response_curve,
+            previous: vec![],
+            frame: vec![],
+            scaled: vec![],
+        }
+    }
+
+    // Returns whether the filter is now on
+    pub(crate) fn toggle(&mut self, kind: FilterKind) -> bool {
+        match kind {
+            FilterKind::Ghosting => {
+                self.is_ghosting = !self.is_ghosting;
+                self.previous.clear();
+                self.is_ghosting
+            }
+            FilterKind::Grid => {
+                self.is_grid = !self.is_grid;
+                self.is_grid
+            }
+            FilterKind::ColorResponse => {
+                self.is_color_response = !self.is_color_response;
+                self.is_color_response
+            }
+        }
+    }
+
+    // Scale the grid needs the frame to be shown at, 1 when it is off or there's no room for it
+    pub(crate) fn grid_scale(&self, integer_scale: u32) -> u32 {
+        if self.is_grid && integer_scale >= 2 {
+            integer_scale
+        } else {
+            1
+        }
+    }
+
+    // Returns the filtered RGBA8888 frame, `grid_scale` times the size of `rgba`.
+    // `gap_color` is what shows between the dots.
+    pub(crate) fn apply(&mut self, rgba: &[u8], width: usize, height: usize, grid_scale: u32, gap_color: Rgba) -> &[u8] {
+        self.frame.clear();
+        self.frame.extend_from_slice(&rgba[..width * height * 4]);
+
+        if self.is_ghosting {
+            if self.previous.len() != self.frame.len() {
+                self.previous = self.frame.clone();
+            }
+            for (current, previous) in self.frame.iter_mut().zip(self.previous.iter_mut()) {
+                let blended = (*current as u16 * (256 - GHOSTING_PERSISTENCE) + *previous as u16 * GHOSTING_PERSISTENCE) >> 8;
+                *current = blended as u8;
+                *previous = blended as u8;
+            }
+        }
+
+        if self.is_color_response {
+            for pixel in self.frame.chunks_exact_mut(4) {
+                for channel in pixel[..3].iter_mut() {
+                    *channel = self.response_curve[*channel as usize];
+                }
+            }
+        }
+
+        if grid_scale < 2 {
+            return &self.frame;
+        }
+
+        // Each dot is a `grid_scale` square, with its last row and column halfway to the gap color
+        let scale = grid_scale as usize;
+        let mut gap_pixel = [0; 4];
+        self.scaled.clear();
+        for row in self.frame.chunks_exact(width * 4) {
+            let row_start = self.scaled.len();
+            for pixel in row.chunks_exact(4) {
+                for ((gap, &color), &gap_color) in gap_pixel.iter_mut().zip(pixel).zip(gap_color.iter()) {
+                    *gap = ((color as u16 + gap_color as u16) / 2) as u8;
+                }
+                for _ in 0..scale - 1 {
+                    self.scaled.extend_from_slice(pixel);
+                }
+                self.scaled.extend_from_slice(&gap_pixel);
+            }
+            let row_end = self.scaled.len();
+            for _ in 0..scale - 2 {
+                self.scaled.extend_from_within(row_start..row_end);
+            }
+            for i in row_start..row_end {
+                let gap = (self.scaled[i] as u16 + gap_color[i % 4] as u16) / 2;
+                self.scaled.push(gap as u8);
+            }
+        }
+        &self.scaled
+    }
+}
@@ -4,12 +4,15 @@ use std::fs::read;
 use crate::cartridge::cartridge::Cartridge;
 use crate::cli::cli;
 use crate::console::{input::JoypadInput, ppu};
+use crate::console::interrupts::InterruptRegBit;
 
 // OAM
 pub(crate) const OAM_START: u16 = 0xFE00;
 pub(crate) const OAM_END: u16 = 0xFE9F;
 // IO
 pub(crate) const JOYPAD_REG: u16 = 0xFF00;
+pub(crate) const SB_REG: u16 = 0xFF01;
+pub(crate) const SC_REG: u16 = 0xFF02;
 // Timer
 pub(crate) const DIV_REG: u16 = 0xFF04;
 pub(crate) const TIMA_REG: u16 = 0xFF05;
@@ -36,7 +39,7 @@ pub(crate) enum LcdControlRegBit {
     WindowTilemapIsAt9C00 = 6,
     // 7	LCD and PPU enable	0=Off, 1=On
     // This bit controls whether the LCD is on and the PPU is active.
-    // Setting it to 0 turns both off, which grants immediate and full access to VRAM, OAM, etc. TODO
+    // Setting it to 0 turns both off, which grants immediate and full access to VRAM, OAM, etc.
     LcdAndPpuEnabled = 7,
 }
 pub(crate) const LCD_STATUS_REG: u16 = 0xFF41;
@@ -67,6 +70,8 @@ pub(crate) const OBP0_REG: u16 = 0xFF48;
 pub(crate) const OBP1_REG: u16 = 0xFF49;
 pub(crate) const WY_REG: u16 = 0xFF4A;
 pub(crate) const WX_REG: u16 = 0xFF4B;
+// CGB speed switch
+pub(crate) const KEY1_REG: u16 = 0xFF4D;
 // Banking, MBC
 pub(crate) const BANK_REG: u16 = 0xFF50;
 // Interrupts
@@ -80,9 +85,8 @@ pub(crate) enum Caller {
 }
 
 pub(crate) struct Mmu {
-    pub(crate) sysclock: u16, // 16bit internal DIV reg, incremented by Timer, TODO relocate?
+    pub(crate) sysclock: u16, // 16bit internal DIV reg, brought up to date by Timer::sync, TODO relocate?
     pub(crate) is_booting: bool,
-    pub(crate) oam_dma_src_addr: Option<u16>,
     pub(crate) active_input: HashSet<JoypadInput>,  // TODO this doesn't belong here
     cartridge: Option<Cartridge>,
     debug_address: Option<u16>,
@@ -91,6 +95,8 @@ pub(crate) struct Mmu {
     rom: [u8; 0x8000usize],
     ram: [u8; 0x8000usize],
     pub(crate) ppu_mode: ppu::StatMode,
+    pub(crate) cgb_mode: bool, // TODO CGB support, always false for now
+    pub(crate) double_speed: bool,
 }
 
 impl Mmu {
@@ -98,7 +104,6 @@ impl Mmu {
         let mut mmu = Mmu {
             sysclock: 0,
             is_booting: true,
-            oam_dma_src_addr: None,
             active_input: HashSet::from([]),
             cartridge,
             debug_address: None, // Option::from(LCD_CONTROL_REG),
@@ -107,6 +112,8 @@ impl Mmu {
             rom: [0; 0x8000],
             ram: [0; 0x8000],
             ppu_mode: ppu::StatMode::HBlank,
+            cgb_mode: false,
+            double_speed: false,
         };
 
         if !skip_boot {
@@ -136,17 +143,6 @@ impl Mmu {
         result
     }
 
-    pub(crate) fn read_16(&mut self, address: u16, caller: Caller) -> u16 {
-        // TODO WIP
-        let lo_address: u16 = address;
-        let hi_address: u16 = address + 1;
-
-        let lo: u8 = self.read_8(lo_address, caller);
-        let hi: u8 = self.read_8(hi_address, caller);
-
-        ((hi as u16) << 8) | (lo as u16)
-    }
-
     pub(crate) fn read_buffer(&mut self, start: u16, end: u16, caller: Caller) -> Vec<u8> {
         let mut result = vec![0; end as usize - start as usize];
         for address in start..end {
@@ -174,19 +170,6 @@ impl Mmu {
         result
     }
 
-    pub(crate) fn write_16(&mut self, address: u16, value: u16, caller: Caller) -> u16 {
-        let mut hi: u8 = ((value & 0xFF00) >> 8) as u8;
-        let mut lo: u8 = (value & 0x00FF) as u8;
-
-        // TODO WIP
-        let lo_address: u16 = address;
-        let hi_address: u16 = address + 1;
-
-        lo = self.write_8(lo_address, lo, caller);
-        hi = self.write_8(hi_address, hi, caller);
-        ((hi as u16) << 8) | (lo as u16)
-    }
-
     #[allow(unused_variables)]
     fn read_8_rom(&mut self, address: u16, caller: Caller) -> u8 {
         let result = match address {
@@ -251,7 +234,7 @@ impl Mmu {
                     || (self.ppu_mode != ppu::StatMode::OamSearch && self.ppu_mode != ppu::StatMode::PixelTransfer) {
                     self.ram[ram_address]
                 } else {
-                    println!("OAM LOCKED by PPU: Cannot read address {:04X}.", address);
+                    // println!("OAM LOCKED by PPU: Cannot read address {:04X}.", address);
                     0xFF
                 }
             }
@@ -271,6 +254,14 @@ impl Mmu {
             0xFF00..=0xFF7F => {
                 match address {
                     JOYPAD_REG => self.read_joypad_reg(self.ram[ram_address]),
+                    KEY1_REG => {
+                        if self.cgb_mode {
+                            // Bit 7 - current speed, bit 0 - switch armed, unused bits read as 1
+                            0x7E | ((self.double_speed as u8) << 7) | (self.ram[ram_address] & 0x01)
+                        } else {
+                            0xFF
+                        }
+                    }
                     _ => self.ram[ram_address]
                 }
             }
@@ -351,7 +342,7 @@ impl Mmu {
                     || (self.ppu_mode != ppu::StatMode::OamSearch && self.ppu_mode != ppu::StatMode::PixelTransfer) {
                     self.ram[ram_address] = value;
                 } else {
-                    println!("OAM LOCKED by PPU: Cannot write address {:04X}.", address);
+                    // println!("OAM LOCKED by PPU: Cannot write address {:04X}.", address);
                 }
             }
 
@@ -435,25 +426,20 @@ impl Mmu {
                         }
                     }
                     LCD_CONTROL_REG => {
-                        if self.ppu_mode == ppu::StatMode::VBlank {
-                            // println!("{:?} Writing CONTROL during VBlank: {:02X}", caller, value);
-                            self.ram[ram_address] = value;
-                        }
-                        // else if caller == Caller::PPU {
-                        //     println!("PPU writing CONTROL outside VBlank: {:02X}", value);
-                        //     self.ram[ram_address] = value;
-                        // }
-                        else {
-                            let curr_value = self.ram[ram_address];
-                            let adjusted_value = value | (curr_value & 0x80); // Enforce not clearing bit 7
-                            // println!("{:?} Writing CONTROL outside VBlank, clearing bit 7 is not allowed: {:02X} -> {:02X}",
-                            //     caller, value, adjusted_value);
-                            self.ram[ram_address] = adjusted_value;
+                        // Turning the LCD off outside VBlank is allowed, but could damage a real DMG's screen
+                        let is_turned_off = self.ram[ram_address] & 0x80 != 0 && value & 0x80 == 0;
+                        if is_turned_off && self.ppu_mode != ppu::StatMode::VBlank {
+                            println!("WARNING: LCD turned off outside VBlank (mode {}).", self.ppu_mode as u8);
                         }
+                        self.ram[ram_address] = value;
                     }
                     DMA_REG => {
-                        self.ram[ram_address] = value;
-                        self.oam_dma_src_addr = Option::from((value as u16) << 8);
+                        self.ram[ram_address] = value; // Transfer is started by Ppu::start_oam_dma
+                    }
+
+                    // CGB SPEED SWITCH -- Only the "armed" bit 0 is writable
+                    KEY1_REG => {
+                        self.ram[ram_address] = value & 0x01;
                     }
 
                     // BANKING
@@ -483,6 +469,27 @@ impl Mmu {
         self.ram[ram_address]
     }
 
+    pub(crate) fn request_interrupt(&mut self, interrupt: InterruptRegBit) {
+        self.ram[(IF_REG - 0x8000) as usize] |= 1 << interrupt as u8;
+    }
+
+    // Any selected joypad line pulled low (pressed), used to wake the CPU from STOP.
+    pub(crate) fn is_joypad_line_low(&mut self) -> bool {
+        self.read_8(JOYPAD_REG, Caller::CPU) & 0x0F != 0x0F
+    }
+
+    // CGB only: if KEY1 is armed, STOP switches CPU speed instead of entering low-power mode.
+    pub(crate) fn try_speed_switch(&mut self) -> bool {
+        let key1_address = (KEY1_REG - 0x8000) as usize;
+        if self.cgb_mode && self.ram[key1_address] & 0x01 == 0x01 {
+            self.ram[key1_address] = 0;
+            self.double_speed = !self.double_speed;
+            true
+        } else {
+            false
+        }
+    }
+
     fn load_bootrom(&mut self) {
         let bootrom = read(cli::BOOTROM_FILEPATH).unwrap();
         let size = min(bootrom.len(), 0x100);
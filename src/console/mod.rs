@@ -4,13 +4,27 @@ pub(crate) mod cpu_registers;
 pub(crate) mod debugger;
 pub(crate) mod disassembler;
 pub(crate) mod display;
+pub(crate) mod framebuffer;
 pub(crate) mod mmu;
+pub(crate) mod recorder;
 pub(crate) mod timer;
+pub(crate) mod upscaler;
 
 mod alu;
+mod bus;
+mod gif_export;
+mod ini;
 mod input;
 mod instruction;
 mod interrupts;
+mod key_bindings;
+mod lcd_filter;
+mod osd;
+mod palette_config;
+mod pixel_fifo;
 mod ppu;
 mod register;
+mod scheduler;
+mod screenshot;
+mod serial;
 mod sprite_attribute;
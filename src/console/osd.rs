@@ -0,0 +1,297 @@
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+use crate::console::console::{CLOCK_HZ, CYCLES_PER_FRAME};
+use crate::console::framebuffer::Rgba;
+use crate::console::input::JoypadInput;
+
+// On-screen display, drawn on its own transparent layer at the LCD's resolution and blended over
+// the frame, so screenshots, recordings and GIFs don't include it.
+//  - Messages: status updates (palette changed, screenshot saved, ...), bottom left, fading out.
+//  - Stats: presented frames per second and emulation speed, top left.
+//  - Input: the joypad buttons currently held, top right.
+
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+const CHAR_WIDTH: usize = GLYPH_WIDTH + 1;
+const LINE_HEIGHT: usize = GLYPH_HEIGHT + 2; // Including the background's padding
+
+const MESSAGE_DURATION: Duration = Duration::from_millis(2500);
+const MESSAGE_FADE: Duration = Duration::from_millis(500); // At the end of MESSAGE_DURATION
+const MAX_MESSAGES: usize = 4;
+const STATS_INTERVAL: Duration = Duration::from_secs(1);
+
+const TEXT_COLOR: Rgba = [255, 255, 255, 255];
+const RELEASED_COLOR: Rgba = [96, 96, 96, 255];
+const BACKGROUND_COLOR: Rgba = [0, 0, 0, 160];
+
+struct Message {
+    text: String,
+    shown_at: Instant,
+}
+
+pub(crate) struct Osd {
+    width: usize,
+    height: usize,
+    messages: VecDeque<Message>, // Oldest first
+    is_stats_enabled: bool,
+    is_input_enabled: bool,
+    stats_start: Instant,
+    stats_frames: u32,
+    stats_text: String,
+    rgba: Vec<u8>,
+}
+
+impl Osd {
+    pub(crate) fn new(width: usize, height: usize) -> Osd {
+        Osd {
+            width,
+            height,
+            messages: VecDeque::with_capacity(MAX_MESSAGES),
+            is_stats_enabled: false,
+            is_input_enabled: false,
+            stats_start: Instant::now(),
+            stats_frames: 0,
+            stats_text: String::new(),
+            rgba: vec![0; width * height * 4],
+        }
+    }
+
+    pub(crate) fn message(&mut self, text: &str) {
+        if self.messages.len() == MAX_MESSAGES {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(Message {
+            text: text.to_string(),
+            shown_at: Instant::now(),
+        });
+    }
+
+    pub(crate) fn toggle_stats(&mut self) -> bool {
+        self.is_stats_enabled = !self.is_stats_enabled;
+        self.stats_start = Instant::now();
+        self.stats_frames = 0;
+        self.stats_text = String::from("FPS -");
+        self.is_stats_enabled
+    }
+
+    pub(crate) fn toggle_input(&mut self) -> bool {
+        self.is_input_enabled = !self.is_input_enabled;
+        self.is_input_enabled
+    }
+
+    // Called once per presented frame. Returns the RGBA8888 layer, or None when there is nothing on it.
+    pub(crate) fn render(&mut self, active_input: &HashSet<JoypadInput>) -> Option<&[u8]> {
+        self.update_stats();
+        self.messages.retain(|message| message.shown_at.elapsed() < MESSAGE_DURATION);
+        if self.messages.is_empty() && !self.is_stats_enabled && !self.is_input_enabled {
+            return None;
+        }
+
+        self.rgba.fill(0);
+
+        if self.is_stats_enabled {
+            let text = self.stats_text.clone();
+            self.draw_text_box(&text, 0, 0, TEXT_COLOR, 255);
+        }
+
+        if self.is_input_enabled {
+            self.draw_input(active_input);
+        }
+
+        // Newest at the bottom, long messages wrap
+        let max_chars = (self.width - 2) / CHAR_WIDTH;
+        let lines: Vec<(String, u8)> = self.messages.iter()
+            .flat_map(|message| {
+                let alpha = Osd::fade(message.shown_at.elapsed());
+                Osd::wrap(&message.text, max_chars).into_iter().map(move |line| (line, alpha))
+            })
+            .collect();
+        let mut y = self.height.saturating_sub(lines.len() * LINE_HEIGHT);
+        for (line, alpha) in lines {
+            self.draw_text_box(&line, 0, y, TEXT_COLOR, alpha);
+            y += LINE_HEIGHT;
+        }
+
+        Option::from(self.rgba.as_slice())
+    }
+
+    // Emulated T-cycles per second over the interval, compared to the DMG's clock
+    fn update_stats(&mut self) {
+        self.stats_frames += 1;
+        let elapsed = self.stats_start.elapsed();
+        if elapsed < STATS_INTERVAL {
+            return;
+        }
+
+        let fps = self.stats_frames as f64 / elapsed.as_secs_f64();
+        let speed = fps * CYCLES_PER_FRAME as f64 / CLOCK_HZ as f64 * 100.0;
+        self.stats_text = format!("FPS {:.1} SPEED {:.0}%", fps, speed);
+        self.stats_start = Instant::now();
+        self.stats_frames = 0;
+    }
+
+    // Opacity of a message shown `elapsed` ago
+    fn fade(elapsed: Duration) -> u8 {
+        let remaining = MESSAGE_DURATION.saturating_sub(elapsed);
+        if remaining >= MESSAGE_FADE {
+            255
+        } else {
+            (255.0 * remaining.as_secs_f64() / MESSAGE_FADE.as_secs_f64()) as u8
+        }
+    }
+
+    // Splits `text` into lines of at most `max_chars`, between words where possible
+    fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+        let mut lines = vec![];
+        let mut line = String::new();
+        for word in text.split(' ') {
+            let mut word = word.to_string();
+            while word.chars().count() > max_chars {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                let rest = word.split_off(word.char_indices().nth(max_chars).unwrap().0);
+                lines.push(word);
+                word = rest;
+            }
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+        lines
+    }
+
+    // D-pad as a cross of squares, then the buttons as text. Held ones are lit.
+    fn draw_input(&mut self, active_input: &HashSet<JoypadInput>) {
+        let color = |input: JoypadInput| if active_input.contains(&input) { TEXT_COLOR } else { RELEASED_COLOR };
+        let buttons = [
+            ("B", color(JoypadInput::InputKeyB)),
+            ("A", color(JoypadInput::InputKeyA)),
+            ("SEL", color(JoypadInput::InputKeySelect)),
+            ("STA", color(JoypadInput::InputKeyStart)),
+        ];
+        let buttons_width: usize = buttons.iter().map(|(text, _)| text.len() * CHAR_WIDTH + 1).sum();
+        let dpad_size = 9;
+        let width = 1 + dpad_size + 2 + buttons_width;
+        let height = dpad_size + 2;
+        let x = self.width - width;
+        self.fill_rect(x, 0, width, height, BACKGROUND_COLOR, 255);
+
+        let (dpad_x, dpad_y) = (x + 1, 1);
+        let cell = dpad_size / 3;
+        self.fill_rect(dpad_x + cell, dpad_y, cell, cell, color(JoypadInput::InputKeyUp), 255);
+        self.fill_rect(dpad_x, dpad_y + cell, cell, cell, color(JoypadInput::InputKeyLeft), 255);
+        self.fill_rect(dpad_x + cell, dpad_y + cell, cell, cell, RELEASED_COLOR, 255);
+        self.fill_rect(dpad_x + 2 * cell, dpad_y + cell, cell, cell, color(JoypadInput::InputKeyRight), 255);
+        self.fill_rect(dpad_x + cell, dpad_y + 2 * cell, cell, cell, color(JoypadInput::InputKeyDown), 255);
+
+        let mut text_x = dpad_x + dpad_size + 2;
+        for (text, color) in buttons {
+            self.draw_text(text, text_x, (height - GLYPH_HEIGHT) / 2, color, 255);
+            text_x += text.len() * CHAR_WIDTH + 1;
+        }
+    }
+
+    // Text on a translucent box, `y` is the top of the box
+    fn draw_text_box(&mut self, text: &str, x: usize, y: usize, color: Rgba, alpha: u8) {
+        let width = text.chars().count() * CHAR_WIDTH + 1;
+        self.fill_rect(x, y, width, LINE_HEIGHT, BACKGROUND_COLOR, alpha);
+        self.draw_text(text, x + 1, y + 1, color, alpha);
+    }
+
+    fn draw_text(&mut self, text: &str, x: usize, y: usize, color: Rgba, alpha: u8) {
+        for (i, c) in text.chars().enumerate() {
+            let glyph = Osd::glyph(c);
+            for (row, bits) in glyph.iter().enumerate() {
+                for column in 0..GLYPH_WIDTH {
+                    if bits & (0b100 >> column) != 0 {
+                        self.fill_rect(x + i * CHAR_WIDTH + column, y + row, 1, 1, color, alpha);
+                    }
+                }
+            }
+        }
+    }
+
+    // Clipped to the layer. `alpha` scales the color's own alpha.
+    fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Rgba, alpha: u8) {
+        let pixel = [color[0], color[1], color[2], (color[3] as u16 * alpha as u16 / 255) as u8];
+        for row in y..(y + height).min(self.height) {
+            for column in x..(x + width).min(self.width) {
+                let i = (row * self.width + column) * 4;
+                self.rgba[i..i + 4].copy_from_slice(&pixel);
+            }
+        }
+    }
+
+    // 3x5 pixels, one row per byte with the leftmost pixel in bit 2. Lowercase is drawn as
+    // uppercase, anything else without a glyph as '?'.
+    fn glyph(c: char) -> [u8; GLYPH_HEIGHT] {
+        match c.to_ascii_uppercase() {
+            'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+            'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+            'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+            'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+            'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+            'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+            'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+            'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+            'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+            'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+            'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+            'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+            'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+            'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+            'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+            'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+            'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+            'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+            'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+            'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+            'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+            'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+            'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+            'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+            'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+            'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+            '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+            '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+            '2' => [0b110, 0b001, 0b010, 0b100, 0b111],
+            '3' => [0b110, 0b001, 0b010, 0b001, 0b110],
+            '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+            '5' => [0b111, 0b100, 0b110, 0b001, 0b110],
+            '6' => [0b011, 0b100, 0b110, 0b101, 0b010],
+            '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+            '8' => [0b010, 0b101, 0b010, 0b101, 0b010],
+            '9' => [0b010, 0b101, 0b011, 0b001, 0b110],
+            ' ' => [0b000, 0b000, 0b000, 0b000, 0b000],
+            '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+            ',' => [0b000, 0b000, 0b000, 0b010, 0b100],
+            ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+            '!' => [0b010, 0b010, 0b010, 0b000, 0b010],
+            '?' => [0b110, 0b001, 0b010, 0b000, 0b010],
+            '\'' => [0b010, 0b010, 0b000, 0b000, 0b000],
+            '"' => [0b101, 0b101, 0b000, 0b000, 0b000],
+            '%' => [0b101, 0b001, 0b010, 0b100, 0b101],
+            '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+            '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+            '=' => [0b000, 0b111, 0b000, 0b111, 0b000],
+            '_' => [0b000, 0b000, 0b000, 0b000, 0b111],
+            '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+            '(' => [0b001, 0b010, 0b010, 0b010, 0b001],
+            ')' => [0b100, 0b010, 0b010, 0b010, 0b100],
+            '[' => [0b110, 0b100, 0b100, 0b100, 0b110],
+            ']' => [0b011, 0b001, 0b001, 0b001, 0b011],
+            '<' => [0b001, 0b010, 0b100, 0b010, 0b001],
+            '>' => [0b100, 0b010, 0b001, 0b010, 0b100],
+            '^' => [0b010, 0b101, 0b000, 0b000, 0b000],
+            _ => [0b110, 0b001, 0b010, 0b000, 0b010],
+        }
+    }
+}
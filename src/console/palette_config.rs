@@ -0,0 +1,184 @@
+use std::fs;
+use std::path::Path;
+use crate::console::framebuffer;
+use crate::console::framebuffer::{Palette, Rgba};
+use crate::console::ini;
+use crate::console::ini::{Ini, IniSection};
+use crate::console::ppu::Layer;
+
+// User palettes, one section per palette:
+//
+//   active = DMG Green
+//
+//   [DMG Green]
+//   bg = 9BBC0F 8BAC0F 306230 0F380F
+//   obj0 = ...
+//   obj1 = ...
+//   window = ...   (optional, defaults to bg)
+//
+// Colors are hex RRGGBB, lightest shade first. `active` is updated whenever the palette is changed,
+// so it is used again on the next run.
+
+pub(crate) const PALETTE_CONFIG_PATH: &str = "./config/palettes.ini";
+
+// Not from the file, a new random palette every time it is selected
+pub(crate) const RANDOM_PALETTE_NAME: &str = "Random";
+
+const ACTIVE_KEY: &str = "active";
+
+pub(crate) struct NamedPalette {
+    pub(crate) name: String,
+    pub(crate) palette: Palette,
+}
+
+pub(crate) struct PaletteConfig {
+    pub(crate) palettes: Vec<NamedPalette>, // Always ends with the random palette
+    pub(crate) active: usize,
+}
+
+impl PaletteConfig {
+    // Writes the presets to `path` if it doesn't exist yet. Invalid palettes are reported and skipped.
+    pub(crate) fn load(path: &str) -> PaletteConfig {
+        if !Path::new(path).exists() {
+            PaletteConfig::write_defaults(path);
+        }
+
+        let mut palettes = vec![];
+        let mut active_name = String::from(framebuffer::DEFAULT_PALETTE_NAME);
+        match fs::read_to_string(path) {
+            Ok(text) => {
+                let ini = Ini::parse(&text, path);
+                for section in ini.sections.iter() {
+                    if section.name.is_empty() {
+                        if let Some(name) = section.get(ACTIVE_KEY) {
+                            active_name = name.to_string();
+                        }
+                    } else if section.name.eq_ignore_ascii_case(RANDOM_PALETTE_NAME) {
+                        println!("WARNING: {}: '{}' is reserved for the random palette, ignoring it.", path, section.name);
+                    } else if let Some(palette) = PaletteConfig::parse_palette(section, path) {
+                        palettes.push(NamedPalette {
+                            name: section.name.clone(),
+                            palette,
+                        });
+                    }
+                }
+            }
+            Err(error) => println!("WARNING: Failed to read palettes '{}': {}", path, error),
+        }
+
+        if palettes.is_empty() {
+            palettes.push(NamedPalette {
+                name: String::from(framebuffer::DEFAULT_PALETTE_NAME),
+                palette: framebuffer::DEFAULT_PALETTE,
+            });
+        }
+        palettes.push(NamedPalette {
+            name: String::from(RANDOM_PALETTE_NAME),
+            palette: Palette::random(),
+        });
+
+        let active = palettes.iter()
+            .position(|named| named.name.eq_ignore_ascii_case(&active_name))
+            .unwrap_or(0);
+
+        PaletteConfig {
+            palettes,
+            active,
+        }
+    }
+
+    pub(crate) fn active_palette(&self) -> &Palette {
+        &self.palettes[self.active].palette
+    }
+
+    // Selects the next palette, and remembers it in the file at `path`
+    pub(crate) fn cycle(&mut self, path: &str) {
+        self.active = (self.active + 1) % self.palettes.len();
+        let named = &mut self.palettes[self.active];
+        if named.name == RANDOM_PALETTE_NAME {
+            named.palette = Palette::random();
+        }
+
+        let text = fs::read_to_string(path).unwrap_or_default();
+        let new_text = ini::set_top_level_value(&text, ACTIVE_KEY, &named.name);
+        if let Err(error) = fs::write(path, new_text) {
+            println!("WARNING: Failed to save the active palette to '{}': {}", path, error);
+        }
+    }
+
+    fn parse_palette(section: &IniSection, path: &str) -> Option<Palette> {
+        let mut colors = [[[0; 4]; 4]; 4];
+        for (key, layer) in [("bg", Layer::Background), ("obj0", Layer::Obj0), ("obj1", Layer::Obj1)] {
+            colors[layer as usize] = match section.get(key) {
+                Some(value) => PaletteConfig::parse_shades(value)
+                    .or_else(|| {
+                        println!("WARNING: {}: [{}] '{}' needs 4 hex colors (RRGGBB), skipping the palette.", path, section.name, key);
+                        None
+                    })?,
+                None => {
+                    println!("WARNING: {}: [{}] is missing '{}', skipping the palette.", path, section.name, key);
+                    return None;
+                }
+            };
+        }
+        colors[Layer::Window as usize] = match section.get("window") {
+            Some(value) => PaletteConfig::parse_shades(value).unwrap_or_else(|| {
+                println!("WARNING: {}: [{}] 'window' needs 4 hex colors (RRGGBB), using 'bg'.", path, section.name);
+                colors[Layer::Background as usize]
+            }),
+            None => colors[Layer::Background as usize],
+        };
+
+        Option::from(Palette {
+            colors,
+        })
+    }
+
+    fn parse_shades(value: &str) -> Option<[Rgba; 4]> {
+        let colors: Vec<Rgba> = value.split_whitespace()
+            .map(PaletteConfig::parse_color)
+            .collect::<Option<_>>()?;
+        colors.try_into().ok()
+    }
+
+    // RRGGBB, optionally prefixed with '#'
+    fn parse_color(hex: &str) -> Option<Rgba> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if hex.len() != 6 {
+            return None;
+        }
+        let rgb = u32::from_str_radix(hex, 16).ok()?;
+        Option::from([(rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8, 255])
+    }
+
+    fn format_shades(shades: &[Rgba; 4]) -> String {
+        shades.iter()
+            .map(|color| format!("{:02X}{:02X}{:02X}", color[0], color[1], color[2]))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn write_defaults(path: &str) {
+        let mut text = String::from("# GamBoy color palettes, cycled with 'c'.\n\
+            # Colors are hex RRGGBB, lightest shade first. 'window' is optional and defaults to 'bg'.\n\n");
+        text.push_str(&format!("{} = {}\n", ACTIVE_KEY, framebuffer::DEFAULT_PALETTE_NAME));
+        for (name, palette) in framebuffer::PRESETS.iter() {
+            let colors = &palette.colors;
+            text.push_str(&format!("\n[{}]\n", name));
+            text.push_str(&format!("bg = {}\n", PaletteConfig::format_shades(&colors[Layer::Background as usize])));
+            if colors[Layer::Window as usize] != colors[Layer::Background as usize] {
+                text.push_str(&format!("window = {}\n", PaletteConfig::format_shades(&colors[Layer::Window as usize])));
+            }
+            text.push_str(&format!("obj0 = {}\n", PaletteConfig::format_shades(&colors[Layer::Obj0 as usize])));
+            text.push_str(&format!("obj1 = {}\n", PaletteConfig::format_shades(&colors[Layer::Obj1 as usize])));
+        }
+
+        let result = Path::new(path).parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(path, text));
+        match result {
+            Ok(()) => println!("Wrote default palettes to '{}'.", path),
+            Err(error) => println!("WARNING: Failed to write default palettes to '{}': {}", path, error),
+        }
+    }
+}
@@ -0,0 +1,460 @@
+use std::collections::VecDeque;
+use crate::console::mmu;
+use crate::console::mmu::{Caller, LcdControlRegBit, Mmu};
+use crate::console::ppu::{Layer, Lcd, Ppu, LCD_WIDTH};
+use crate::console::sprite_attribute::SpriteAttribute;
+
+// Mode 3 is rendered one dot at a time: a fetcher reads the tile map and tile data (2 dots per step)
+// and pushes rows of 8 pixels into the background FIFO, which shifts out one pixel per dot onto the LCD.
+// Mode 3 gets longer when the FIFO stalls: discarding SCX fine scroll pixels, restarting the fetcher
+// for the window, and pausing for sprite fetches.
+// https://gbdev.io/pandocs/pixel_fifo.html
+
+// The first fetch of every line is thrown away
+const DUMMY_FETCH_DOTS: u8 = 6;
+const SPRITE_FETCH_DOTS: u8 = 6;
+// Setting the fetcher up for the window, even when it had nothing to throw away
+const WINDOW_RESTART_DOTS: u8 = 6;
+
+#[derive(Copy, Clone)]
+struct BgPixel {
+    color: u8, // 0-3, before applying the palette
+    is_window: bool,
+}
+
+#[derive(Copy, Clone)]
+struct SpritePixel {
+    color: u8, // 0-3, before applying the palette, 0 is transparent
+    palette_is_obp1: bool,
+    bg_window_over_obj: bool,
+}
+
+const TRANSPARENT: SpritePixel = SpritePixel {
+    color: 0,
+    palette_is_obp1: false,
+    bg_window_over_obj: false,
+};
+
+#[derive(Copy, Clone, PartialEq)]
+enum FetcherStep {
+    GetTile,
+    GetTileDataLow,
+    GetTileDataHigh,
+    Push,
+}
+
+pub(crate) struct PixelFifo {
+    ly: u8,
+    lx: u8,                 // Next pixel to be output on the LCD
+    pub(crate) dots: u64,   // Dots spent in mode 3 so far
+    pub(crate) is_hidden: bool, // Pixels are not output to the LCD
+    delay: u8,              // Dots left of the dummy fetch
+    discard: u8,            // SCX fine scroll pixels still to be dropped
+    // Fetcher
+    step: FetcherStep,
+    is_second_dot: bool,    // Each fetcher step takes 2 dots
+    fetcher_x: u8,          // Tile column, relative to SCX (or to the window's left edge)
+    tile_index: u8,
+    tile_data_low: u8,
+    tile_data_high: u8,
+    in_window: bool,
+    window_line: Option<u8>,           // Row of the window to draw, None until WY==LY has happened this frame
+    window_from_line_start: bool,      // WX was 166 on the previous line, the window covers this whole line
+    pub(crate) window_rendered: bool,  // Window was drawn on this line, its line counter moves on
+    pub(crate) window_at_line_end: bool, // Window was started by WX=166 at the last pixel
+    // FIFOs
+    bg_fifo: VecDeque<BgPixel>,
+    sprite_fifo: VecDeque<SpritePixel>,
+    sprites: VecDeque<SpriteAttribute>, // Sprites on this line not fetched yet, in fetch order
+    sprite_fetch_dots: u8,              // Dots left of the current sprite fetch
+}
+
+impl PixelFifo {
+    pub(crate) fn new() -> PixelFifo {
+        PixelFifo {
+            ly: 0,
+            lx: 0,
+            dots: 0,
+            is_hidden: false,
+            delay: DUMMY_FETCH_DOTS,
+            discard: 0,
+            step: FetcherStep::GetTile,
+            is_second_dot: false,
+            fetcher_x: 0,
+            tile_index: 0,
+            tile_data_low: 0,
+            tile_data_high: 0,
+            in_window: false,
+            window_line: None,
+            window_from_line_start: false,
+            window_rendered: false,
+            window_at_line_end: false,
+            bg_fifo: VecDeque::with_capacity(16),
+            sprite_fifo: VecDeque::with_capacity(16),
+            sprites: VecDeque::with_capacity(10),
+            sprite_fetch_dots: 0,
+        }
+    }
+
+    // Reset at the start of mode 3. SCX fine scroll is latched here, everything else is read when used.
+    // `sprites` are the ones visible on this line, `window_line` is the PPU's internal window line counter
+    // once the window has been triggered by WY this frame.
+    pub(crate) fn start_line(
+            &mut self,
+            mmu: &mut Mmu,
+            ly: u8,
+            sprites: &[SpriteAttribute],
+            window_line: Option<u8>,
+            window_from_line_start: bool) {
+        *self = PixelFifo::new();
+        self.ly = ly;
+        self.window_line = window_line;
+        self.window_from_line_start = window_from_line_start;
+        self.discard = mmu.read_8(mmu::SCX_REG, Caller::PPU) % 8;
+
+        // Sprites are fetched left to right, ones past the right edge are never fetched. Sprites at X=0
+        // are fetched (and stall mode 3) like any other, but have no pixels left to draw.
+        // Combined with already fetched sprites keeping their pixels, this gives DMG priority:
+        // smaller X wins, OAM order breaks ties.
+        let mut sprites: Vec<SpriteAttribute> = sprites.iter()
+            .filter(|attr| attr.x < 168)
+            .copied()
+            .collect();
+        sprites.sort_by_key(|attr| attr.x); // stable, so OAM order breaks ties
+        self.sprites = VecDeque::from(sprites);
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.lx as usize >= LCD_WIDTH
+    }
+
+    // Lower bound of dots until the line is done, at most one pixel is output per dot.
+    pub(crate) fn min_dots_remaining(&self) -> u64 {
+        (LCD_WIDTH - self.lx as usize) as u64
+    }
+
+    pub(crate) fn tick(&mut self, mmu: &mut Mmu, lcd: &mut Lcd) {
+        self.dots += 1;
+
+        if self.delay > 0 {
+            self.delay -= 1;
+            return;
+        }
+
+        if self.sprite_fetch_dots > 0 {
+            self.sprite_fetch_dots -= 1;
+            if self.sprite_fetch_dots == 0 {
+                self.fetch_sprite(mmu);
+            }
+            return;
+        }
+
+        if !self.in_window && !self.window_rendered {
+            self.try_start_window(mmu);
+        }
+
+        // A sprite starting at this pixel pauses the FIFO, its fetch starts once the background fetcher
+        // is between fetches and the FIFO has pixels to mix it with.
+        let mut is_sprite_due = self.discard == 0
+            && self.sprites.front().is_some_and(|attr| attr.x <= self.lx + 8);
+        // Objects aren't fetched at all while OBJ is disabled, the ones due are skipped without a stall
+        if is_sprite_due && !PixelFifo::is_obj_enabled(mmu) {
+            let lx = self.lx;
+            self.sprites.retain(|attr| attr.x > lx + 8);
+            is_sprite_due = false;
+        }
+        if is_sprite_due && !self.bg_fifo.is_empty() && self.is_fetcher_idle() {
+            self.sprite_fetch_dots = SPRITE_FETCH_DOTS - 1; // This dot is the first one
+            return;
+        }
+
+        self.fetcher_tick(mmu);
+
+        if !is_sprite_due && !self.bg_fifo.is_empty() {
+            self.shift_out(mmu, lcd);
+        }
+    }
+
+    fn is_obj_enabled(mmu: &mut Mmu) -> bool {
+        mmu.read_8(mmu::LCD_CONTROL_REG, Caller::PPU) & (1 << LcdControlRegBit::ObjEnabled as u8) != 0
+    }
+
+    fn is_fetcher_idle(&self) -> bool {
+        self.step == FetcherStep::Push || (self.step == FetcherStep::GetTile && !self.is_second_dot)
+    }
+
+    fn is_window_enabled(mmu: &mut Mmu) -> bool {
+        let lcd_control = mmu.read_8(mmu::LCD_CONTROL_REG, Caller::PPU);
+        lcd_control & (1 << LcdControlRegBit::WindowEnabled as u8) != 0
+            && lcd_control & (1 << LcdControlRegBit::BackgroundAndWindowEnabled as u8) != 0
+    }
+
+    fn try_start_window(&mut self, mmu: &mut Mmu) {
+        if self.window_line.is_none() || !PixelFifo::is_window_enabled(mmu) {
+            return;
+        }
+
+        let wx = mmu.read_8(mmu::WX_REG, Caller::PPU);
+        let is_reached = if self.window_from_line_start {
+            self.discard == 0
+        } else if wx == 0 {
+            // WX=0 is checked while the SCX fine scroll is still being discarded, so the window's
+            // position stutters with SCX % 8
+            true
+        } else {
+            self.discard == 0 && self.lx + 7 >= wx
+        };
+        if !is_reached {
+            return;
+        }
+
+        // Restart the fetcher on the window's tile map, the FIFO stalls while it fetches. When the
+        // window starts before the first fetch (e.g. WX=7), there is nothing to throw away but the
+        // restart still costs its dots.
+        if self.bg_fifo.is_empty() && self.step == FetcherStep::GetTile && !self.is_second_dot {
+            self.delay = WINDOW_RESTART_DOTS;
+        }
+        self.in_window = true;
+        self.window_rendered = true;
+        self.window_at_line_end = wx == 166;
+        self.bg_fifo.clear();
+        self.fetcher_x = 0;
+        self.step = FetcherStep::GetTile;
+        self.is_second_dot = false;
+
+        // WX 0-6 starts the window offscreen to the left, its first 7 - WX pixels are dropped
+        if wx < 7 && !self.window_from_line_start {
+            self.discard += 7 - wx;
+        }
+    }
+
+    // LCDC bit 5 cleared mid-line: the fetcher goes back to the background at the current position
+    fn stop_window(&mut self, mmu: &mut Mmu) {
+        let fine_scroll = mmu.read_8(mmu::SCX_REG, Caller::PPU) % 8;
+        let next_x = self.lx as u16 + self.bg_fifo.len() as u16 + fine_scroll as u16;
+        self.in_window = false;
+        self.fetcher_x = (next_x / 8) as u8;
+    }
+
+    fn fetcher_tick(&mut self, mmu: &mut Mmu) {
+        if self.step == FetcherStep::Push {
+            // Only pushes when the FIFO is empty, retried every dot
+            if self.bg_fifo.is_empty() {
+                for bit in (0..8).rev() {
+                    let low = (self.tile_data_low >> bit) & 1;
+                    let high = (self.tile_data_high >> bit) & 1;
+                    self.bg_fifo.push_back(BgPixel {
+                        color: (high << 1) | low,
+                        is_window: self.in_window,
+                    });
+                }
+                self.fetcher_x = self.fetcher_x.wrapping_add(1);
+                self.step = FetcherStep::GetTile;
+            }
+            return;
+        }
+
+        if !self.is_second_dot {
+            self.is_second_dot = true;
+            return;
+        }
+        self.is_second_dot = false;
+
+        self.step = match self.step {
+            FetcherStep::GetTile => {
+                if self.in_window && !PixelFifo::is_window_enabled(mmu) {
+                    self.stop_window(mmu);
+                }
+                self.tile_index = self.read_tile_index(mmu);
+                FetcherStep::GetTileDataLow
+            }
+            FetcherStep::GetTileDataLow => {
+                let address = self.tile_data_address(mmu);
+                self.tile_data_low = mmu.read_8(address, Caller::PPU);
+                FetcherStep::GetTileDataHigh
+            }
+            FetcherStep::GetTileDataHigh => {
+                let address = self.tile_data_address(mmu) + 1;
+                self.tile_data_high = mmu.read_8(address, Caller::PPU);
+                FetcherStep::Push
+            }
+            FetcherStep::Push => unreachable!(),
+        };
+    }
+
+    // Row within the background (or window) map
+    fn map_y(&mut self, mmu: &mut Mmu) -> u8 {
+        if self.in_window {
+            self.window_line.unwrap_or(0)
+        } else {
+            self.ly.wrapping_add(mmu.read_8(mmu::SCY_REG, Caller::PPU))
+        }
+    }
+
+    fn read_tile_index(&mut self, mmu: &mut Mmu) -> u8 {
+        let lcd_control = mmu.read_8(mmu::LCD_CONTROL_REG, Caller::PPU);
+        let (tilemap_at_9c00_bit, tilemap_col) = if self.in_window {
+            (LcdControlRegBit::WindowTilemapIsAt9C00, self.fetcher_x)
+        } else {
+            let scx = mmu.read_8(mmu::SCX_REG, Caller::PPU);
+            (LcdControlRegBit::BackgroundTilemapIsAt9C00, (scx / 8).wrapping_add(self.fetcher_x))
+        };
+        let tilemap_address: u16 = if lcd_control & (1 << tilemap_at_9c00_bit as u8) != 0 { 0x9C00 } else { 0x9800 };
+        let tilemap_row = self.map_y(mmu) / 8;
+
+        mmu.read_8(tilemap_address + tilemap_row as u16 * 32 + (tilemap_col % 32) as u16, Caller::PPU)
+    }
+
+    fn tile_data_address(&mut self, mmu: &mut Mmu) -> u16 {
+        let lcd_control = mmu.read_8(mmu::LCD_CONTROL_REG, Caller::PPU);
+        let tile_row = (self.map_y(mmu) % 8) as u16;
+        let tile_address = if lcd_control & (1 << LcdControlRegBit::AddressingMode8000 as u8) != 0 {
+            0x8000 + self.tile_index as u16 * 16
+        } else {
+            (0x9000 + (self.tile_index as i8) as i32 * 16) as u16 // signed
+        };
+        tile_address + tile_row * 2
+    }
+
+    fn fetch_sprite(&mut self, mmu: &mut Mmu) {
+        let attr = self.sprites.pop_front().unwrap();
+
+        let is_16_sprite = mmu.read_8(mmu::LCD_CONTROL_REG, Caller::PPU)
+            & (1 << LcdControlRegBit::SpriteSizeIs16 as u8) != 0;
+        let sprite_height: u8 = if is_16_sprite { 16 } else { 8 };
+        let tile_index = if is_16_sprite { attr.tile_index & 0xFE } else { attr.tile_index };
+
+        let mut tile_row = self.ly.wrapping_sub(attr.y.wrapping_sub(16)) % sprite_height;
+        if attr.flip_y {
+            tile_row = sprite_height - tile_row - 1;
+        }
+
+        let tile_address = 0x8000 + tile_index as u16 * 16 + tile_row as u16 * 2;
+        let low = mmu.read_8(tile_address, Caller::PPU);
+        let high = mmu.read_8(tile_address + 1, Caller::PPU);
+
+        while self.sprite_fifo.len() < 8 {
+            self.sprite_fifo.push_back(TRANSPARENT);
+        }
+
+        for col in 0..8u8 {
+            // Pixels left of the current position (sprite partially offscreen to the left) are dropped
+            let x = (attr.x as i16 - 8) + col as i16;
+            if x < self.lx as i16 {
+                continue;
+            }
+
+            let bit = if attr.flip_x { col } else { 7 - col };
+            let color = (((high >> bit) & 1) << 1) | ((low >> bit) & 1);
+
+            // Already fetched sprites keep their opaque pixels
+            let slot = &mut self.sprite_fifo[(x - self.lx as i16) as usize];
+            if slot.color == 0 {
+                *slot = SpritePixel {
+                    color,
+                    palette_is_obp1: attr.palette_is_obp1,
+                    bg_window_over_obj: attr.bg_window_over_obj,
+                };
+            }
+        }
+    }
+
+    fn shift_out(&mut self, mmu: &mut Mmu, lcd: &mut Lcd) {
+        let bg_pixel = self.bg_fifo.pop_front().unwrap();
+        if self.discard > 0 {
+            self.discard -= 1;
+            return;
+        }
+        let sprite_pixel = self.sprite_fifo.pop_front().unwrap_or(TRANSPARENT);
+
+        let lcd_control = mmu.read_8(mmu::LCD_CONTROL_REG, Caller::PPU);
+
+        // On DMG, disabling BG and window blanks them to color 0 (sprites are still drawn on top)
+        let bg_color = if lcd_control & (1 << LcdControlRegBit::BackgroundAndWindowEnabled as u8) != 0 {
+            bg_pixel.color
+        } else {
+            0
+        };
+
+        let is_sprite_visible = sprite_pixel.color > 0
+            && lcd_control & (1 << LcdControlRegBit::ObjEnabled as u8) != 0
+            && !(sprite_pixel.bg_window_over_obj && bg_color > 0);
+
+        let (shade, layer) = if is_sprite_visible {
+            let (palette_address, layer) = if sprite_pixel.palette_is_obp1 {
+                (mmu::OBP1_REG, Layer::Obj1)
+            } else {
+                (mmu::OBP0_REG, Layer::Obj0)
+            };
+            (Ppu::read_palette(mmu.read_8(palette_address, Caller::PPU))[sprite_pixel.color as usize], layer)
+        } else {
+            let layer = if bg_pixel.is_window { Layer::Window } else { Layer::Background };
+            (Ppu::read_palette(mmu.read_8(mmu::BGP_REG, Caller::PPU))[bg_color as usize], layer)
+        };
+
+        if !self.is_hidden {
+            lcd.set_pixel(self.lx as usize, self.ly as usize, shade, layer);
+        }
+        self.lx += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::console::mmu::Mmu;
+    use crate::console::ppu::Lcd;
+    use super::*;
+
+    // Dots mode 3 takes for line 0 with SCX=0
+    fn mode_3_dots(lcd_control: u8, wx: u8, sprites: &[SpriteAttribute], window_line: Option<u8>) -> u64 {
+        let mut mmu = Mmu::new(None, true);
+        mmu.write_8(mmu::LCD_CONTROL_REG, lcd_control, Caller::PPU);
+        mmu.write_8(mmu::SCX_REG, 0, Caller::PPU);
+        mmu.write_8(mmu::WX_REG, wx, Caller::PPU);
+        let mut lcd = Lcd::new();
+        let mut pixel_fifo = PixelFifo::new();
+        pixel_fifo.start_line(&mut mmu, 0, sprites, window_line, false);
+        while !pixel_fifo.is_done() {
+            pixel_fifo.tick(&mut mmu, &mut lcd);
+        }
+        pixel_fifo.dots
+    }
+
+    const LCD_ON: u8 = 0x91; // BG on, tile data at 0x8000
+    const OBJ_ON: u8 = 0x02;
+    const WINDOW_ON: u8 = 0x20;
+
+    fn sprite(x: u8) -> SpriteAttribute {
+        SpriteAttribute::new(&[16, x, 0, 0])
+    }
+
+    #[test]
+    fn sprite_at_x0_stalls_mode_3() {
+        let base = mode_3_dots(LCD_ON | OBJ_ON, 0, &[], None);
+        let at_x0 = mode_3_dots(LCD_ON | OBJ_ON, 0, &[sprite(0)], None);
+        let at_x8 = mode_3_dots(LCD_ON | OBJ_ON, 0, &[sprite(8)], None);
+        assert_eq!(base, 172);
+        assert!(at_x0 > base);
+        // Both line up with the first tile, so they cost the same
+        assert_eq!(at_x0, at_x8);
+    }
+
+    #[test]
+    fn sprite_with_obj_disabled_is_not_fetched() {
+        assert_eq!(mode_3_dots(LCD_ON, 0, &[sprite(8), sprite(50)], None), 172);
+    }
+
+    #[test]
+    fn sprite_past_right_edge_is_not_fetched() {
+        assert_eq!(mode_3_dots(LCD_ON | OBJ_ON, 0, &[sprite(168)], None), 172);
+    }
+
+    #[test]
+    fn window_restart_stalls_mode_3() {
+        let base = mode_3_dots(LCD_ON | WINDOW_ON, 7, &[], None);
+        assert_eq!(base, 172);
+        assert_eq!(mode_3_dots(LCD_ON | WINDOW_ON, 7, &[], Option::from(0)), base + 6);
+        assert_eq!(mode_3_dots(LCD_ON | WINDOW_ON, 8, &[], Option::from(0)), base + 6);
+        assert_eq!(mode_3_dots(LCD_ON | WINDOW_ON, 50, &[], Option::from(0)), base + 6);
+    }
+}
@@ -1,7 +1,10 @@
-use crate::console::interrupts::{InterruptRegBit, Interrupts};
+use crate::console::bus::M_CYCLE;
+use crate::console::interrupts::InterruptRegBit;
 use crate::console::mmu;
 use crate::console::mmu::{Caller, Mmu};
+use crate::console::pixel_fifo::PixelFifo;
 use crate::console::register::{Register};
+use crate::console::scheduler::{EventKind, Scheduler};
 use crate::console::sprite_attribute::SpriteAttribute;
 
 pub(crate) const STAT_MODES: [StatMode; 4] = [
@@ -13,13 +16,20 @@ pub(crate) const STAT_MODES: [StatMode; 4] = [
 
 // From Michael Steil, Ultimate Game Boy Talk
 // (Gekkio (Mooneye) has HBlank=50 and OAM=21)
-const MODE_DURATION: [usize; 4] = [
+// PixelTransfer is the shortest possible mode 3 (no fine scroll, window or sprites), it ends when the
+// pixel FIFO has output the whole line, and HBlank lasts for the rest of the 456 dot line.
+const MODE_DURATION: [u64; 4] = [
     51 * 4,             // = 204    HBlank (0)
     (20 + 43 + 51) * 4, // = 456    VBlank (1)
     20 * 4,             // = 80     OAM (2)
     43 * 4,             // = 172    PixelTransfer (3)
 ];
 
+const SPRITES_PER_LINE: usize = 10;
+
+// 160 bytes, copied one per M-cycle
+const OAM_DMA_LENGTH: u64 = 160;
+
 #[allow(dead_code)]
 const MODE_LINE_RANGE: [(u8, u8); 4] = [
     (0, 144),   // HBlank
@@ -36,36 +46,88 @@ pub(crate) enum StatMode {
     PixelTransfer = 3,
 }
 
-#[allow(dead_code)]
-#[derive(PartialEq, Debug)]
-enum DrawMode {
-    Background,
-    Sprites,
-    Window,
+pub(crate) const LCD_WIDTH: usize = 160;
+pub(crate) const LCD_HEIGHT: usize = 144;
+pub(crate) const LCD_PIXELS: usize = LCD_WIDTH * LCD_HEIGHT;
+
+// Which layer an output pixel came from, so frontends can color them differently
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) enum Layer {
+    Background = 0,
+    Window = 1,
+    Obj0 = 2, // Sprite using OBP0
+    Obj1 = 3, // Sprite using OBP1
 }
 
+// Row-major output of the PPU, one byte per pixel
 pub(crate) struct Lcd {
     pub(crate) width: usize,
     pub(crate) height: usize,
-    pub(crate) data: Vec<Vec<u8>>,
+    pub(crate) data: [u8; LCD_PIXELS],              // DMG shades, 0 (lightest) - 3 (darkest), after BGP/OBP0/OBP1
+    pub(crate) layers: Option<Box<[u8; LCD_PIXELS]>>, // Layer of each pixel, only kept when enabled
 }
 
 impl Lcd {
     pub(crate) fn new() -> Lcd {
-        let width: usize = 160;
-        let height: usize = 144;
-
         Lcd {
-            width,
-            height,
-            data: vec![vec![0; width]; height],
+            width: LCD_WIDTH,
+            height: LCD_HEIGHT,
+            data: [0; LCD_PIXELS],
+            layers: None,
+        }
+    }
+
+    pub(crate) fn set_layers_enabled(&mut self, enabled: bool) {
+        self.layers = if enabled {
+            Option::from(Box::new([Layer::Background as u8; LCD_PIXELS]))
+        } else {
+            None
+        };
+    }
+
+    pub(crate) fn set_pixel(&mut self, x: usize, y: usize, shade: u8, layer: Layer) {
+        let i = y * self.width + x;
+        self.data[i] = shade;
+        if let Some(layers) = &mut self.layers {
+            layers[i] = layer as u8;
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.data.fill(0);
+        if let Some(layers) = &mut self.layers {
+            layers.fill(Layer::Background as u8);
         }
     }
 }
 
+// OAM DMA transfer in progress, byte n is copied at the end of M-cycle n after `start`
+struct DmaTransfer {
+    source: u16,
+    start: u64, // Scheduler time the startup delay ended
+    copied: u64,
+}
+
+impl DmaTransfer {
+    fn copy_until(&mut self, mmu: &mut Mmu, now: u64) {
+        let due = (now.saturating_sub(self.start) / M_CYCLE as u64).min(OAM_DMA_LENGTH);
+        for offset in self.copied..due {
+            let value = mmu.read_8(self.source + offset as u16, Caller::PPU);
+            // Always written, OAM is not locked for the DMA controller in any PPU mode
+            mmu.write_8(mmu::OAM_START + offset as u16, value, Caller::PPU);
+        }
+        self.copied = self.copied.max(due);
+    }
+
+    fn is_copying(&self, now: u64) -> bool {
+        self.start + (M_CYCLE as u64) <= now && now <= self.start + OAM_DMA_LENGTH * M_CYCLE as u64
+    }
+}
+
 #[allow(dead_code)]
 pub(crate) struct Ppu {
-    mode_cycle_count: usize,
+    oam_dma: Option<DmaTransfer>,
+    oam_dma_restarted: Option<DmaTransfer>, // Previous transfer, still running during the startup of its replacement
     scy: Register,
     scx: Register,
     // ly: Register,
@@ -78,14 +140,26 @@ pub(crate) struct Ppu {
     wx: Register,
     lcd_control: Register,
     lcd_status: Register,
-    sprite_attributes: [SpriteAttribute; 40],
+    line_sprites: Vec<SpriteAttribute>, // Selected during OAM search
+    is_lcd_on: bool,
+    is_lcd_starting: bool, // First line after turning the LCD on
+    is_frame_hidden: bool, // First frame after turning the LCD on is not output
+    stat_line: bool,       // All enabled STAT sources ORed together, the interrupt fires on its rising edge
+    // Window
+    is_wy_triggered: bool,        // Latched when WY==LY at the start of a line, until the end of the frame
+    window_line: u8,              // Internal counter, only moves on lines where the window was drawn
+    window_from_line_start: bool, // WX=166 on the previous line
+    line_start: u64, // Scheduler time the current line started (its OAM search)
+    pixel_fifo: PixelFifo,
     pub(crate) lcd: Lcd,
+    pub(crate) frames: u64, // Frames completed (VBlanks entered) since power on
 }
 
 impl Ppu {
     pub(crate) fn new() -> Ppu {
         Ppu {
-            mode_cycle_count: 0,
+            oam_dma: None,
+            oam_dma_restarted: None,
             scy: Register::new(mmu::SCY_REG),
             scx: Register::new(mmu::SCX_REG),
             // ly: Register::new(mmu::LY_REG),
@@ -98,83 +172,220 @@ impl Ppu {
             wx: Register::new(mmu::WX_REG),
             lcd_control: Register::new(mmu::LCD_CONTROL_REG),
             lcd_status: Register::new(mmu::LCD_STATUS_REG),
-            sprite_attributes: [SpriteAttribute::new(&[0; 4]); 40],
+            line_sprites: Vec::with_capacity(SPRITES_PER_LINE),
+            is_lcd_on: false,
+            is_lcd_starting: false,
+            is_frame_hidden: false,
+            stat_line: false,
+            is_wy_triggered: false,
+            window_line: 0,
+            window_from_line_start: false,
+            line_start: 0,
+            pixel_fifo: PixelFifo::new(),
             lcd: Lcd::new(),
+            frames: 0,
         }
     }
 
-    // TODO Fix OAM DMA timing (mooneye acceptance/add_sp_e_timing, jp_timing)
-    pub(crate) fn oam_dma(&mut self, mmu: &mut Mmu) {
-        if let Some(mut src_address) = mmu.oam_dma_src_addr {
-            let value = mmu.read_8(src_address, Caller::PPU);
-            let oam_address = mmu::OAM_START | (src_address & 0xFF);
-
-            // Writes to OAM not allowed in certain PPU modes
-            let lcd_status_value = self.lcd_status.read(mmu, Caller::PPU);
-            let stat_mode = STAT_MODES[(lcd_status_value & 0x03) as usize];
-            if stat_mode != StatMode::PixelTransfer && stat_mode != StatMode::OamSearch {
-                mmu.write_8(oam_address, value, Caller::PPU);
+    // Writing the DMA register starts a transfer after a 1 M-cycle startup delay.
+    // If a transfer was already running, it keeps going (and blocking the bus) during that delay.
+    pub(crate) fn start_oam_dma(&mut self, mmu: &mut Mmu, scheduler: &mut Scheduler) {
+        let mut source = (self.dma.read(mmu, Caller::PPU) as u16) << 8;
+        if source >= 0xE000 {
+            source -= 0x2000; // E000-FFFF reads from the WRAM echo
+        }
+
+        self.oam_dma_restarted = self.oam_dma.take();
+        self.oam_dma = Option::from(DmaTransfer {
+            source,
+            start: scheduler.now + M_CYCLE as u64,
+            copied: 0,
+        });
+        scheduler.schedule(EventKind::OamDmaComplete, (1 + OAM_DMA_LENGTH) * M_CYCLE as u64);
+    }
+
+    // Copy the bytes due by `now`, must be called before anything reads OAM or the PPU changes mode.
+    pub(crate) fn oam_dma(&mut self, mmu: &mut Mmu, now: u64) {
+        if let Some(mut restarted) = self.oam_dma_restarted.take() {
+            let new_start = self.oam_dma.as_ref().map_or(now, |transfer| transfer.start);
+            restarted.copy_until(mmu, now.min(new_start));
+            if now <= new_start {
+                self.oam_dma_restarted = Option::from(restarted);
             }
+        }
 
-            // Increment source address
-            src_address += 1;
-            mmu.oam_dma_src_addr = if (src_address & 0xFF) > 0x9F {
-                None // Stop
-            } else {
-                Option::from(src_address)
-            };
+        if let Some(mut transfer) = self.oam_dma.take() {
+            transfer.copy_until(mmu, now);
+            if transfer.copied < OAM_DMA_LENGTH || transfer.is_copying(now) {
+                self.oam_dma = Option::from(transfer);
+            }
         }
     }
 
-    pub(crate) fn step(&mut self, cycles: u16, interrupts: &mut Interrupts, mmu: &mut Mmu) {
-        if self.lcd_control.check_bit(mmu, mmu::LcdControlRegBit::LcdAndPpuEnabled as u8, Caller::PPU) {
-            // let mode = mmu.ppu_mode;
-            let mode_flag = (self.lcd_status.read(mmu, Caller::PPU) & 0x03) as usize;
-            let mode = STAT_MODES[mode_flag];
-            let mode_duration = MODE_DURATION[mode as usize];
-
-            // HACK Force current line ly to align with current mode
-            // let (mode_y_start, mode_y_end) = MODE_LINE_RANGE[mode as usize];
-            // if self.ly.read(mmu, Caller::PPU) < mode_y_start || mode_y_end <= self.ly.read(mmu, Caller::PPU) {
-            //     self.set_ly(mmu, mode_y_start);
-            // }
-
-            // If time to do so, move to next stat mode
-            if self.mode_cycle_count >= mode_duration {
-                self.move_to_next_stat_mode(mmu, interrupts, mode);
+    // The DMA controller owns the bus while it copies, the CPU can only reach HRAM (and the IO registers)
+    pub(crate) fn is_oam_dma_blocking(&self, now: u64) -> bool {
+        self.oam_dma_restarted.iter()
+            .chain(self.oam_dma.iter())
+            .any(|transfer| transfer.is_copying(now))
+    }
+
+    // LCD and PPU enable (LCDC bit 7) was possibly toggled.
+    // Turning it off stops the PPU: LY and the STAT mode go to 0, which unlocks VRAM and OAM, and the
+    // screen goes blank. Turning it back on restarts from line 0, and that first frame is not shown.
+    pub(crate) fn lcd_control_written(&mut self, mmu: &mut Mmu, scheduler: &mut Scheduler) {
+        let is_lcd_on = self.lcd_control.check_bit(mmu, mmu::LcdControlRegBit::LcdAndPpuEnabled as u8, Caller::PPU);
+        if is_lcd_on == self.is_lcd_on {
+            return;
+        }
+        self.is_lcd_on = is_lcd_on;
+
+        if is_lcd_on {
+            // Line 0 starts without an OAM search, STAT reports mode 0 until pixel transfer
+            self.is_lcd_starting = true;
+            self.is_frame_hidden = true;
+            self.line_start = scheduler.now;
+            self.compare_lyc(mmu);
+            scheduler.schedule(EventKind::PpuModeChange, MODE_DURATION[StatMode::OamSearch as usize]);
+        } else {
+            scheduler.cancel(EventKind::PpuModeChange);
+            mmu.write_8(mmu::LY_REG, 0, Caller::PPU);
+            let stat = self.lcd_status.read(mmu, Caller::PPU);
+            self.lcd_status.write(mmu, stat & 0xFC, Caller::PPU);
+            self.stat_line = false;
+            self.lcd.clear();
+            self.is_wy_triggered = false;
+            self.window_line = 0;
+            self.window_from_line_start = false;
+        }
+    }
+
+    // Handles EventKind::PpuModeChange: the current mode (or VBlank line) is over.
+    pub(crate) fn change_mode(&mut self, mmu: &mut Mmu, scheduler: &mut Scheduler) {
+        if self.is_lcd_starting {
+            self.is_lcd_starting = false;
+            self.line_sprites.clear();
+            self.set_stat_mode(mmu, StatMode::PixelTransfer);
+            self.start_pixel_transfer(mmu);
+            scheduler.schedule(EventKind::PpuModeChange, MODE_DURATION[StatMode::PixelTransfer as usize]);
+            return;
+        }
+
+        let mode_flag = (self.lcd_status.read(mmu, Caller::PPU) & 0x03) as usize;
+
+        // Mode 3 has no fixed length, render up to now and check again once it could be done
+        if STAT_MODES[mode_flag] == StatMode::PixelTransfer {
+            self.sync(mmu, scheduler);
+            if !self.pixel_fifo.is_done() {
+                scheduler.schedule(EventKind::PpuModeChange, self.pixel_fifo.min_dots_remaining());
             }
+            return;
+        }
 
-            // Do mode-specific action
-            match mode {
-                StatMode::OamSearch => {
-                    self.oam_search(mmu);
-                }
-                StatMode::PixelTransfer => {
-                    self.pixel_transfer(mmu);
+        let mode = self.move_to_next_stat_mode(mmu, STAT_MODES[mode_flag]);
+
+        // Do mode-specific action
+        match mode {
+            StatMode::OamSearch => {
+                self.line_start = scheduler.now;
+                self.oam_search(mmu);
+            }
+            StatMode::PixelTransfer => {
+                self.start_pixel_transfer(mmu);
+            }
+            StatMode::HBlank => {}
+            StatMode::VBlank => {
+                self.line_start = scheduler.now;
+                if mmu.read_8(mmu::LY_REG, Caller::PPU) as usize == LCD_HEIGHT {
+                    self.frames += 1;
                 }
-                StatMode::HBlank => {}
-                StatMode::VBlank => {}
+                self.is_frame_hidden = false;
+                self.is_wy_triggered = false;
+                self.window_line = 0;
+                self.window_from_line_start = false;
             }
+        }
 
-            // If LY == LYC
-            let ly = mmu.read_8(mmu::LY_REG, Caller::PPU);
+        scheduler.schedule(EventKind::PpuModeChange, MODE_DURATION[mode as usize]);
+    }
 
-            let lyc = self.lyc.read(mmu, Caller::PPU);
+    // Render mode 3 up to the current time, so that the CPU sees the right STAT mode and its
+    // writes to VRAM, OAM and the PPU registers land at the right pixel.
+    pub(crate) fn sync(&mut self, mmu: &mut Mmu, scheduler: &mut Scheduler) {
+        if mmu.ppu_mode != StatMode::PixelTransfer {
+            return;
+        }
 
-            if ly == lyc {
-                self.lcd_status.set_bit(mmu, mmu::LcdStatRegBit::LycEqLy as u8, true, Caller::PPU);
-                if self.lcd_status.check_bit(mmu, mmu::LcdStatRegBit::LycInterruptEnabled as u8, Caller::PPU) {
-                    interrupts.requested.set_bit(mmu, InterruptRegBit::LcdStat as u8, true, Caller::PPU);
-                }
-            } else {
-                self.lcd_status.set_bit(mmu, mmu::LcdStatRegBit::LycEqLy as u8, false, Caller::PPU);
+        let pixel_transfer_start = self.line_start + MODE_DURATION[StatMode::OamSearch as usize];
+        let dots = scheduler.now - pixel_transfer_start;
+        while !self.pixel_fifo.is_done() && self.pixel_fifo.dots < dots {
+            self.pixel_fifo.tick(mmu, &mut self.lcd);
+        }
+
+        if self.pixel_fifo.is_done() {
+            if self.pixel_fifo.window_rendered {
+                self.window_line = self.window_line.wrapping_add(1);
             }
+            self.window_from_line_start = self.pixel_fifo.window_at_line_end;
+
+            self.set_stat_mode(mmu, StatMode::HBlank);
+            let line_end = self.line_start + MODE_DURATION[StatMode::VBlank as usize];
+            scheduler.schedule_at(EventKind::PpuModeChange, line_end);
+        }
+    }
+
+    // Must be called whenever LY or LYC changes.
+    pub(crate) fn compare_lyc(&mut self, mmu: &mut Mmu) {
+        let ly = mmu.read_8(mmu::LY_REG, Caller::PPU);
+        let lyc = self.lyc.read(mmu, Caller::PPU);
+        self.lcd_status.set_bit(mmu, mmu::LcdStatRegBit::LycEqLy as u8, ly == lyc, Caller::PPU);
+        self.update_stat_line(mmu, false);
+    }
+
+    // The CPU wrote STAT. On DMG the write behaves as if the HBlank, VBlank and LY==LYC sources were
+    // enabled for one cycle, which fires a spurious interrupt in those if the line was low (not in OAM search).
+    pub(crate) fn lcd_status_written(&mut self, mmu: &mut Mmu) {
+        if !self.is_lcd_on {
+            return;
+        }
+
+        let stat = self.lcd_status.read(mmu, Caller::PPU);
+        // Bits 3, 4 and 6, the OAM source (bit 5) isn't affected
+        if !self.stat_line && self.stat_sources(stat | 0x58, false) {
+            mmu.request_interrupt(InterruptRegBit::LcdStat);
+        }
+        self.stat_line = self.stat_sources(stat, false);
+    }
+
+    // Recompute the STAT interrupt line, requesting the interrupt if it went from low to high.
+    // While any source keeps the line high, other sources becoming active don't fire again.
+    fn update_stat_line(&mut self, mmu: &mut Mmu, is_vblank_start: bool) {
+        if !self.is_lcd_on {
+            return;
         }
 
-        self.mode_cycle_count += cycles as usize;
+        let stat = self.lcd_status.read(mmu, Caller::PPU);
+        let line = self.stat_sources(stat, is_vblank_start);
+        if line && !self.stat_line {
+            mmu.request_interrupt(InterruptRegBit::LcdStat);
+        }
+        self.stat_line = line;
     }
 
-    fn read_palette(byte: u8) -> [u8; 4] {
+    fn stat_sources(&self, stat: u8, is_vblank_start: bool) -> bool {
+        let is_enabled = |bit: mmu::LcdStatRegBit| stat & (1 << bit as u8) != 0;
+        let mode_source = match STAT_MODES[(stat & 0x03) as usize] {
+            // The mode 0 reported while the LCD starts up is not a real HBlank
+            StatMode::HBlank => is_enabled(mmu::LcdStatRegBit::HBlankInterruptEnabled) && !self.is_lcd_starting,
+            // Line 144 also briefly raises the OAM source, as if a mode 2 was starting
+            StatMode::VBlank => is_enabled(mmu::LcdStatRegBit::VBlankInterruptEnabled)
+                || (is_vblank_start && is_enabled(mmu::LcdStatRegBit::OamInterruptEnabled)),
+            StatMode::OamSearch => is_enabled(mmu::LcdStatRegBit::OamInterruptEnabled),
+            StatMode::PixelTransfer => false,
+        };
+        mode_source || (is_enabled(mmu::LcdStatRegBit::LycEqLy) && is_enabled(mmu::LcdStatRegBit::LycInterruptEnabled))
+    }
+
+    pub(crate) fn read_palette(byte: u8) -> [u8; 4] {
         // Bit 1-0 - Color for index 0
         // Bit 3-2 - Color for index 1
         // Bit 5-4 - Color for index 2
@@ -194,248 +405,117 @@ impl Ppu {
             new_ly = 0;
         }
 
-        mmu.write_8(mmu::LY_REG, new_ly, Caller::PPU)
+        mmu.write_8(mmu::LY_REG, new_ly, Caller::PPU);
+        self.compare_lyc(mmu);
+        new_ly
     }
 
-    fn set_stat_mode(&mut self, mmu: &mut Mmu, new_mode: StatMode, interrupts: &mut Interrupts) {
+    fn set_stat_mode(&mut self, mmu: &mut Mmu, new_mode: StatMode) {
         let curr_value = self.lcd_status.read(mmu, Caller::PPU);
         let new_value = (curr_value & 0xFC) | (new_mode as u8);
         self.lcd_status.write(mmu, new_value, Caller::PPU);
 
-        // Request interrupts
-        match new_mode {
-            StatMode::HBlank => {
-                if self.lcd_status.check_bit(mmu, mmu::LcdStatRegBit::HBlankInterruptEnabled as u8, Caller::PPU) {
-                    interrupts.requested.set_bit(mmu, InterruptRegBit::LcdStat as u8, true, Caller::PPU);
-                }
-            }
-            StatMode::VBlank => {
-                // Always request VBLank interrupt
-                interrupts.requested.set_bit(mmu, InterruptRegBit::VBlank as u8, true, Caller::PPU);
+        if new_mode == StatMode::VBlank {
+            // Always request VBLank interrupt
+            mmu.request_interrupt(InterruptRegBit::VBlank);
 
-                if self.lcd_status.check_bit(mmu, mmu::LcdStatRegBit::VBlankInterruptEnabled as u8, Caller::PPU) {
-                    interrupts.requested.set_bit(mmu, InterruptRegBit::LcdStat as u8, true, Caller::PPU);
-                }
-                // Mooneye includes a check if OAM INT enabled when swithcing to VBLank also, but I'm not sure why
-                if self.lcd_status.check_bit(mmu, mmu::LcdStatRegBit::OamInterruptEnabled as u8, Caller::PPU) {
-                    interrupts.requested.set_bit(mmu, InterruptRegBit::LcdStat as u8, true, Caller::PPU);
-                }
-            }
-            StatMode::OamSearch => {
-                if self.lcd_status.check_bit(mmu, mmu::LcdStatRegBit::OamInterruptEnabled as u8, Caller::PPU) {
-                    interrupts.requested.set_bit(mmu, InterruptRegBit::LcdStat as u8, true, Caller::PPU);
-                }
-            }
-            StatMode::PixelTransfer => {
-                // Do nothing
-            }
+            // The OAM source only pulses at the start of VBlank, drop it again right after
+            self.update_stat_line(mmu, true);
         }
+        self.update_stat_line(mmu, false);
     }
 
-    fn move_to_next_stat_mode(&mut self, mmu: &mut Mmu, interrupts: &mut Interrupts, curr_mode: StatMode) {
-        self.mode_cycle_count = 0;
-
-        match curr_mode {
-            StatMode::OamSearch => {
-                self.set_stat_mode(mmu, StatMode::PixelTransfer, interrupts);
-            }
-            StatMode::PixelTransfer => {
-                self.set_stat_mode(mmu, StatMode::HBlank, interrupts);
-            }
+    // Returns the new mode
+    fn move_to_next_stat_mode(&mut self, mmu: &mut Mmu, curr_mode: StatMode) -> StatMode {
+        let new_mode = match curr_mode {
+            StatMode::OamSearch => StatMode::PixelTransfer,
+            StatMode::PixelTransfer => StatMode::HBlank,
             StatMode::HBlank => {
                 let ly = self.increment_ly(mmu);
                 if ly < 144 {
-                    self.set_stat_mode(mmu, StatMode::OamSearch, interrupts);
+                    StatMode::OamSearch
                 } else {
-                    self.set_stat_mode(mmu, StatMode::VBlank, interrupts);
+                    StatMode::VBlank
                 }
             }
             StatMode::VBlank => {
                 let ly = self.increment_ly(mmu);
                 if ly == 0 { // ly < MODE_LINE_RANGE[StatMode::OamSearch as usize].1 {
-                    self.set_stat_mode(mmu, StatMode::OamSearch, interrupts);
+                    StatMode::OamSearch
+                } else {
+                    return StatMode::VBlank; // Still in VBlank mode, just on a new line.
                 }
-                //else still in VBlank mode, just on a new line.
             }
-        }
+        };
+
+        self.set_stat_mode(mmu, new_mode);
+        new_mode
     }
 
+    // Checks the WY==LY window trigger and selects the (up to 10) sprites on this line, in OAM order. Only Y is checked, so sprites that are
+    // offscreen horizontally still count towards the limit.
     fn oam_search(&mut self, mmu: &mut Mmu) {
         let ly = mmu.read_8(mmu::LY_REG, Caller::PPU);
+        if ly == self.wy.read(mmu, Caller::PPU) {
+            self.is_wy_triggered = true;
+        }
 
-        if ly == 0 {
-            self.lcd_control.read(mmu, Caller::PPU);
-            let obj_enabled = self.lcd_control.check_bit(mmu, mmu::LcdControlRegBit::ObjEnabled as u8, Caller::PPU);
-            if obj_enabled {
-                let attribute_data = mmu.read_buffer(mmu::OAM_START, mmu::OAM_END + 1, Caller::PPU);
-                for i in 0..40 {
-                    let data = &attribute_data[(i * 4)..(i * 4) + 4];
-                    let attr = SpriteAttribute::new(data.try_into().unwrap());
-                    self.sprite_attributes[i] = attr;
+        let is_16_sprite = self.lcd_control.check_bit(mmu, mmu::LcdControlRegBit::SpriteSizeIs16 as u8, Caller::PPU);
+        let sprite_height: u8 = if is_16_sprite { 16 } else { 8 };
+
+        self.line_sprites.clear();
+        let attribute_data = mmu.read_buffer(mmu::OAM_START, mmu::OAM_END + 1, Caller::PPU);
+        for data in attribute_data.chunks_exact(4) {
+            let attr = SpriteAttribute::new(data.try_into().unwrap());
+            let sprite_top_y = attr.y.wrapping_sub(16);
+            if ly.wrapping_sub(sprite_top_y) < sprite_height {
+                self.line_sprites.push(attr);
+                if self.line_sprites.len() == SPRITES_PER_LINE {
+                    break;
                 }
             }
         }
     }
 
-    // TODO: Fix graphics misaligned on screen bug
-    fn pixel_transfer(&mut self, mmu: &mut Mmu) {
-        self.draw_background_line(mmu);
-        self.draw_window_line(mmu);
-        self.draw_sprites_line(mmu);
-    }
-
-    fn draw_background_line(&mut self, mmu: &mut Mmu) {
-        if self.lcd_control.check_bit(mmu, mmu::LcdControlRegBit::BackgroundAndWindowEnabled as u8, Caller::PPU) {
-            self.draw_background_or_window_line(mmu, DrawMode::Background);
-        }
-    }
-
-    fn draw_window_line(&mut self, mmu: &mut Mmu) {
-        if self.lcd_control.check_bit(mmu, mmu::LcdControlRegBit::BackgroundAndWindowEnabled as u8, Caller::PPU)
-                && self.lcd_control.check_bit(mmu, mmu::LcdControlRegBit::WindowEnabled as u8, Caller::PPU) {
-            self.draw_background_or_window_line(mmu, DrawMode::Window);
-        }
+    fn start_pixel_transfer(&mut self, mmu: &mut Mmu) {
+        let ly = mmu.read_8(mmu::LY_REG, Caller::PPU);
+        let window_line = if self.is_wy_triggered { Option::from(self.window_line) } else { None };
+        self.pixel_fifo.start_line(mmu, ly, &self.line_sprites, window_line, self.window_from_line_start);
+        self.pixel_fifo.is_hidden = self.is_frame_hidden;
     }
+}
 
-    fn fetch_tile(mmu: &mut Mmu, tile_address: u16) -> [[u8; 8]; 8] {
-        let tile_bytes: [u8; 16] = mmu.read_buffer(tile_address, tile_address + 16, Caller::PPU)
-            .try_into().unwrap();
-        let mut tile = [[0; 8]; 8];
-        for row in 0..8 {
-            for col in 0..8 {
-                // Possible values = 0,1,2,3 (0b00,0b01,0b10,0b11)
-                let low = ((tile_bytes[row * 2] << col) >> 7) << 1;
-                let high = (tile_bytes[row * 2 + 1] << col) >> 7;
-                tile[row][col] = high + low;
-            }
-        }
-        tile
+#[cfg(test)]
+mod tests {
+    use crate::console::mmu::Mmu;
+    use super::*;
+
+    // Writes STAT with every source disabled while the PPU is in `mode`, returns whether the
+    // STAT interrupt was requested
+    fn stat_write_interrupts(mode: StatMode, is_ly_eq_lyc: bool) -> bool {
+        let mut mmu = Mmu::new(None, true);
+        let mut ppu = Ppu::new();
+        ppu.is_lcd_on = true;
+        mmu.write_8(mmu::LY_REG, 10, Caller::PPU);
+        ppu.lyc.write(&mut mmu, if is_ly_eq_lyc { 10 } else { 20 }, Caller::PPU);
+        ppu.lcd_status.write(&mut mmu, mode as u8, Caller::PPU);
+        ppu.compare_lyc(&mut mmu);
+        mmu.write_8(mmu::IF_REG, 0, Caller::PPU);
+
+        ppu.lcd_status_written(&mut mmu);
+        mmu.read_8(mmu::IF_REG, Caller::PPU) & (1 << InterruptRegBit::LcdStat as u8) != 0
     }
 
-    fn draw_sprites_line(&mut self, mmu: &mut Mmu) {
-        if self.lcd_control.check_bit(mmu, mmu::LcdControlRegBit::ObjEnabled as u8, Caller::PPU) {
-            let tiledata_address: usize = 0x8000;
-            let ly = mmu.read_8(mmu::LY_REG, Caller::PPU);
-
-            for attr in self.sprite_attributes {
-                let is_16_sprite = self.lcd_control.check_bit(mmu, mmu::LcdControlRegBit::SpriteSizeIs16 as u8, Caller::PPU);
-                let sprite_top_y = attr.y.wrapping_sub(16);
-                let sprite_height: u8 = if is_16_sprite { 16 } else { 8 };
-
-                if sprite_top_y <= ly && ly < (sprite_top_y + sprite_height) {
-                    let palette = Ppu::read_palette(self.bgp.read(mmu, Caller::PPU)); // TODO Check attr.palette_is_obp1 to get palette
-
-                    let mut tile_row_n = (ly - sprite_top_y) as usize;
-                    if attr.flip_y {
-                        tile_row_n = sprite_height as usize - tile_row_n - 1;
-                    }
-
-                    let tile_row = if tile_row_n < 8 {
-                        let tile_index_1 = if is_16_sprite { attr.tile_index & 0xFE } else { attr.tile_index } as usize;
-                        let tile_address_1 = (tiledata_address + tile_index_1 * 16) as u16;
-                        let tile1= Ppu::fetch_tile(mmu, tile_address_1);
-                        tile1[tile_row_n]
-                    } else {
-                        let tile_index_2 = if is_16_sprite { attr.tile_index | 0x01 } else { attr.tile_index } as usize;
-                        let tile_address_2 = (tiledata_address + tile_index_2 * 16) as u16;
-                        let tile2 = Ppu::fetch_tile(mmu, tile_address_2);
-                        tile2[tile_row_n - 8]
-                    };
-
-                    for tile_col in 0..8 {
-                        let color_i = tile_row[tile_col as usize];
-
-                        if color_i > 0 {
-                            let color = palette[color_i as usize];
-
-                            // TODO selection priority and drawing priority
-                            let has_priority = true;
-                            let mut x = attr.x.wrapping_sub(8);
-                            let x_offset = if attr.flip_x { 7 - tile_col } else { tile_col };
-                            x = x.wrapping_add(x_offset);
-                            if x as usize >= self.lcd.data[0].len() { continue };
-
-                            let bg_color = self.lcd.data[ly as usize][x as usize];
-
-                            if has_priority || bg_color == 0 {
-                                self.lcd.data[ly as usize][x as usize] = color + 8; // TEMP colors to distinguish sprites
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    #[test]
+    fn stat_write_bug_skips_oam_source() {
+        assert!(!stat_write_interrupts(StatMode::OamSearch, false));
+        assert!(!stat_write_interrupts(StatMode::PixelTransfer, false));
     }
 
-    fn draw_background_or_window_line(&mut self, mmu: &mut Mmu, mode: DrawMode) { // TOO SLOW
-        let ly = mmu.read_8(mmu::LY_REG, Caller::PPU) as i32;
-
-        let y_offset = match mode {
-            DrawMode::Window => {
-                let wy = self.wy.read(mmu, Caller::PPU) as i32;
-                if wy >= ly  { return; } // offscreen
-                -wy
-            },
-            DrawMode::Background => {
-                self.scy.read(mmu, Caller::PPU) as i32
-            },
-            | _ => panic!("Mode {:?} is not allowed here", mode),
-        };
-        let x_offset = match mode {
-            DrawMode::Window => {
-                let wx = self.wx.read(mmu, Caller::PPU);
-                if wx >= (self.lcd.width as u8 + 7) { return; } // offscreen
-                -(wx as i32 - 7)
-            },
-            DrawMode::Background => {
-                self.scx.read(mmu, Caller::PPU) as i32
-            },
-            | _ => panic!("Mode {:?} is not allowed here", mode),
-        };
-
-        let palette = Ppu::read_palette(self.bgp.read(mmu, Caller::PPU));
-        let index_mode_8000 = self.lcd_control.check_bit(mmu, mmu::LcdControlRegBit::AddressingMode8000 as u8, Caller::PPU);
-        let tilemap_base_address: i32 = if index_mode_8000 { 0x8000 } else { 0x9000 };
-        let tilemap_at_9c00_bit = match mode {
-            DrawMode::Window => mmu::LcdControlRegBit::WindowTilemapIsAt9C00,
-            DrawMode::Background | _ => mmu::LcdControlRegBit::BackgroundTilemapIsAt9C00,
-        } as u8;
-        let is_tilemap_at_9c00 = self.lcd_control.check_bit(mmu, tilemap_at_9c00_bit, Caller::PPU);
-        let tilemap_address: u16 = if is_tilemap_at_9c00 { 0x9C00 } else { 0x9800 };
-
-        let tilemap_row = (ly + y_offset) as u8 / 8;
-        let tile_row = (ly + y_offset) as u8 % 8;
-        let tile_index_base_address = tilemap_address + tilemap_row as u16 * 32;
-
-        for x in 0..self.lcd.width as i32 {
-            let tilemap_col = (x + x_offset) as u8 / 8;
-            let tile_col = (x + x_offset) as u8 % 8;
-
-            let tile_index_address = tile_index_base_address + tilemap_col as u16;
-            let tile_index = mmu.read_8(tile_index_address, Caller::PPU);
-            let tile_address_offset: i32 = if index_mode_8000 {
-                tile_index as i32 * 16
-            } else {
-                (tile_index as i8) as i32 * 16 // signed
-            };
-            let tile_address = (tilemap_base_address + tile_address_offset) + tile_row as i32 * 2;
-
-            let tile_byte1 = mmu.read_8(tile_address as u16, Caller::PPU);
-            let tile_byte2 = mmu.read_8(tile_address as u16 + 1, Caller::PPU);
-
-            let low = ((tile_byte1 << tile_col) >> 7) << 1;
-            let high = (tile_byte2 << tile_col) >> 7;
-            let color = high + low;
-
-            if mode == DrawMode::Background || color > 0 { // TODO priority, transparency
-                let pixel_color = palette[color as usize]
-                    // adjust colors of different layers
-                    + match mode {
-                        DrawMode::Window => 4,
-                        _ => 0,
-                    };
-                self.lcd.data[ly as usize][x as usize] = pixel_color;
-            }
-        }
+    #[test]
+    fn stat_write_bug_fires_in_hblank_vblank_and_on_lyc() {
+        assert!(stat_write_interrupts(StatMode::HBlank, false));
+        assert!(stat_write_interrupts(StatMode::VBlank, false));
+        assert!(stat_write_interrupts(StatMode::OamSearch, true));
     }
 }
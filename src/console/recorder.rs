@@ -0,0 +1,115 @@
+use std::fs;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use crate::console::console::CLOCK_HZ;
+use crate::console::screenshot;
+
+pub(crate) const RECORDING_DIR: &str = "./recordings";
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) enum RecordFormat {
+    Y4m, // YUV 4:4:4, playable and encodable as is (e.g. by ffmpeg)
+    Rgb, // Headerless rgb24 frames
+}
+
+impl RecordFormat {
+    pub(crate) fn parse(name: &str) -> Option<RecordFormat> {
+        match name.to_ascii_lowercase().as_str() {
+            "y4m" => Option::from(RecordFormat::Y4m),
+            "rgb" => Option::from(RecordFormat::Rgb),
+            _ => None,
+        }
+    }
+
+    fn extension(&self) -> &str {
+        match self {
+            RecordFormat::Y4m => "y4m",
+            RecordFormat::Rgb => "rgb",
+        }
+    }
+}
+
+// Writes every presented frame, at native resolution and with the palette applied, to an
+// uncompressed video stream.
+pub(crate) struct Recorder {
+    format: RecordFormat,
+    path: PathBuf,
+    writer: BufWriter<File>,
+    width: usize,
+    height: usize,
+    cycles_per_frame: u64, // A frame is presented every this many emulated T-cycles, sets the frame rate
+    frames: u64,
+    frame: Vec<u8>,
+}
+
+impl Recorder {
+    pub(crate) fn start(format: RecordFormat, width: usize, height: usize, cycles_per_frame: u64) -> Result<Recorder, String> {
+        let path = screenshot::timestamped_path(RECORDING_DIR, "gamboy", format.extension());
+        fs::create_dir_all(RECORDING_DIR).map_err(|e| e.to_string())?;
+        let mut writer = BufWriter::new(File::create(&path).map_err(|e| e.to_string())?);
+
+        if format == RecordFormat::Y4m {
+            writeln!(writer, "YUV4MPEG2 W{} H{} F{}:{} Ip A1:1 C444", width, height, CLOCK_HZ, cycles_per_frame)
+                .map_err(|e| e.to_string())?;
+        }
+
+        Ok(Recorder {
+            format,
+            path,
+            writer,
+            width,
+            height,
+            cycles_per_frame,
+            frames: 0,
+            frame: Vec::with_capacity(width * height * 3),
+        })
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // `rgba` is a whole RGBA8888 frame
+    pub(crate) fn record(&mut self, rgba: &[u8]) -> Result<(), String> {
+        self.frame.clear();
+        match self.format {
+            RecordFormat::Y4m => {
+                self.writer.write_all(b"FRAME\n").map_err(|e| e.to_string())?;
+                Recorder::rgba_to_yuv444(rgba, self.width * self.height, &mut self.frame);
+            }
+            RecordFormat::Rgb => {
+                for pixel in rgba.chunks_exact(4).take(self.width * self.height) {
+                    self.frame.extend_from_slice(&pixel[..3]);
+                }
+            }
+        }
+        self.writer.write_all(&self.frame).map_err(|e| e.to_string())?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    // Flushes the stream, and prints how to encode raw RGB since it has no header.
+    pub(crate) fn finish(mut self) -> Result<u64, String> {
+        self.writer.flush().map_err(|e| e.to_string())?;
+        if self.format == RecordFormat::Rgb {
+            println!("Encode with: ffmpeg -f rawvideo -pixel_format rgb24 -video_size {}x{} -framerate {}/{} -i {} out.mp4",
+                self.width, self.height, CLOCK_HZ, self.cycles_per_frame, self.path.display());
+        }
+        Ok(self.frames)
+    }
+
+    // BT.601, limited range. Planes are written one after the other: Y, then Cb, then Cr.
+    fn rgba_to_yuv444(rgba: &[u8], pixels: usize, out: &mut Vec<u8>) {
+        out.resize(pixels * 3, 0);
+        let (y_plane, chroma) = out.split_at_mut(pixels);
+        let (cb_plane, cr_plane) = chroma.split_at_mut(pixels);
+
+        for (i, pixel) in rgba.chunks_exact(4).take(pixels).enumerate() {
+            let (r, g, b) = (pixel[0] as i32, pixel[1] as i32, pixel[2] as i32);
+            y_plane[i] = (16 + ((66 * r + 129 * g + 25 * b + 128) >> 8)) as u8;
+            cb_plane[i] = (128 + ((-38 * r - 74 * g + 112 * b + 128) >> 8)) as u8;
+            cr_plane[i] = (128 + ((112 * r - 94 * g - 18 * b + 128) >> 8)) as u8;
+        }
+    }
+}
@@ -0,0 +1,104 @@
+// Components only run when something they own is due (or when the CPU touches their registers),
+// instead of being stepped on every M-cycle. Each pending event is stamped with the absolute
+// T-cycle it fires at, and there is at most one pending event of each kind.
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) enum EventKind {
+    PpuModeChange,  // Current STAT mode (or VBlank line) is over
+    TimerOverflow,  // TIMA overflowed one M-cycle ago, reload it from TMA
+    OamDmaComplete, // Last byte of an OAM DMA transfer is due
+    SerialBit,      // Next bit of a serial transfer is shifted out
+}
+
+struct Event {
+    time: u64,
+    kind: EventKind,
+}
+
+pub(crate) struct Scheduler {
+    pub(crate) now: u64, // T-cycles since power on
+    events: Vec<Event>,
+}
+
+impl Scheduler {
+    pub(crate) fn new() -> Scheduler {
+        Scheduler {
+            now: 0,
+            events: Vec::with_capacity(4),
+        }
+    }
+
+    // (Re)schedule an event `cycles` T-cycles from now, replacing any pending event of the same kind.
+    pub(crate) fn schedule(&mut self, kind: EventKind, cycles: u64) {
+        self.schedule_at(kind, self.now + cycles);
+    }
+
+    pub(crate) fn schedule_at(&mut self, kind: EventKind, time: u64) {
+        self.cancel(kind);
+        self.events.push(Event {
+            time,
+            kind,
+        });
+    }
+
+    pub(crate) fn cancel(&mut self, kind: EventKind) {
+        self.events.retain(|event| event.kind != kind);
+    }
+
+    pub(crate) fn cycles_until_next(&self) -> Option<u64> {
+        self.events.iter()
+            .map(|event| event.time.saturating_sub(self.now))
+            .min()
+    }
+
+    // Remove and return the earliest event due at or before `until`, moving time forward to it
+    // so that events scheduled by its handler are relative to when it fired.
+    pub(crate) fn pop_due(&mut self, until: u64) -> Option<EventKind> {
+        let (i, time) = self.events.iter()
+            .enumerate()
+            .map(|(i, event)| (i, event.time))
+            .min_by_key(|&(_, time)| time)?;
+
+        if time > until {
+            return None;
+        }
+
+        self.now = self.now.max(time);
+        Some(self.events.remove(i).kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_fire_in_time_order() {
+        let mut scheduler = Scheduler::new();
+        scheduler.schedule(EventKind::SerialBit, 40);
+        scheduler.schedule(EventKind::TimerOverflow, 12);
+        scheduler.schedule(EventKind::PpuModeChange, 20);
+        assert_eq!(scheduler.cycles_until_next(), Some(12));
+
+        assert_eq!(scheduler.pop_due(30), Some(EventKind::TimerOverflow));
+        assert_eq!(scheduler.now, 12);
+        assert_eq!(scheduler.pop_due(30), Some(EventKind::PpuModeChange));
+        assert_eq!(scheduler.now, 20);
+        assert_eq!(scheduler.pop_due(30), None);
+        assert_eq!(scheduler.cycles_until_next(), Some(20));
+    }
+
+    #[test]
+    fn rescheduling_replaces_the_pending_event() {
+        let mut scheduler = Scheduler::new();
+        scheduler.schedule(EventKind::TimerOverflow, 12);
+        scheduler.schedule(EventKind::TimerOverflow, 100);
+        assert_eq!(scheduler.pop_due(50), None);
+        assert_eq!(scheduler.pop_due(100), Some(EventKind::TimerOverflow));
+        assert_eq!(scheduler.pop_due(1000), None);
+
+        scheduler.schedule(EventKind::TimerOverflow, 4);
+        scheduler.cancel(EventKind::TimerOverflow);
+        assert_eq!(scheduler.cycles_until_next(), None);
+    }
+}
@@ -0,0 +1,72 @@
+use std::fs;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub(crate) const SCREENSHOT_DIR: &str = "./screenshots";
+
+// Writes an RGBA8888 frame as a PNG, each pixel repeated `scale` times in both directions.
+pub(crate) fn save_png(path: &Path, rgba: &[u8], width: usize, height: usize, scale: usize) -> Result<(), String> {
+    let scaled = scale_nearest(rgba, width, height, scale);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), (width * scale) as u32, (height * scale) as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer.write_image_data(&scaled).map_err(|e| e.to_string())
+}
+
+// e.g. ./screenshots/gamboy_20240131_235959_123.png (UTC)
+pub(crate) fn timestamped_path(dir: &str, prefix: &str, extension: &str) -> PathBuf {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let time_of_day = secs % 86_400;
+
+    let file_name = format!("{}_{:04}{:02}{:02}_{:02}{:02}{:02}_{:03}.{}",
+        prefix,
+        year, month, day,
+        time_of_day / 3600, (time_of_day / 60) % 60, time_of_day % 60,
+        since_epoch.subsec_millis(),
+        extension);
+    Path::new(dir).join(file_name)
+}
+
+fn scale_nearest(rgba: &[u8], width: usize, height: usize, scale: usize) -> Vec<u8> {
+    if scale <= 1 {
+        return rgba[..width * height * 4].to_vec();
+    }
+
+    let mut scaled = Vec::with_capacity(width * height * 4 * scale * scale);
+    for row in rgba.chunks_exact(width * 4).take(height) {
+        let mut scaled_row = Vec::with_capacity(row.len() * scale);
+        for pixel in row.chunks_exact(4) {
+            for _ in 0..scale {
+                scaled_row.extend_from_slice(pixel);
+            }
+        }
+        for _ in 0..scale {
+            scaled.extend_from_slice(&scaled_row);
+        }
+    }
+    scaled
+}
+
+// Days since 1970-01-01 to (year, month, day), from Howard Hinnant's date algorithms
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
@@ -0,0 +1,52 @@
+use crate::console::mmu;
+use crate::console::mmu::{Caller, Mmu};
+use crate::console::register::Register;
+use crate::console::scheduler::{EventKind, Scheduler};
+
+// Internal clock is 8192Hz = one bit every 512 T-cycles
+const BIT_DURATION: u64 = 512;
+
+// No link cable is ever connected, so a transfer shifts in $FF and completes only when
+// this Game Boy provides the clock.
+pub(crate) struct Serial {
+    bits_left: u8,
+    sb: Register, // FF01 — SB: Serial transfer data
+    sc: Register, // FF02 — SC: Serial transfer control
+}
+
+impl Serial {
+    pub(crate) fn new() -> Serial {
+        Serial {
+            bits_left: 0,
+            sb: Register::new(mmu::SB_REG),
+            sc: Register::new(mmu::SC_REG),
+        }
+    }
+
+    // Bit 7 - Transfer enable, bit 0 - Clock select (1=Internal)
+    pub(crate) fn control_written(&mut self, mmu: &mut Mmu, scheduler: &mut Scheduler) {
+        let sc = self.sc.read(mmu, Caller::CPU);
+        if sc & 0x81 == 0x81 {
+            self.bits_left = 8;
+            scheduler.schedule(EventKind::SerialBit, BIT_DURATION);
+        } else {
+            self.bits_left = 0;
+            scheduler.cancel(EventKind::SerialBit);
+        }
+    }
+
+    // Returns true when the transfer is complete and the serial interrupt should be requested.
+    pub(crate) fn shift_bit(&mut self, mmu: &mut Mmu, scheduler: &mut Scheduler) -> bool {
+        let sb = self.sb.read(mmu, Caller::CPU);
+        self.sb.write(mmu, (sb << 1) | 0x01, Caller::CPU);
+
+        self.bits_left -= 1;
+        if self.bits_left > 0 {
+            scheduler.schedule(EventKind::SerialBit, BIT_DURATION);
+            false
+        } else {
+            self.sc.set_bit(mmu, 7, false, Caller::CPU);
+            true
+        }
+    }
+}
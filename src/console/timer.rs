@@ -1,13 +1,16 @@
+use crate::console::bus::M_CYCLE;
 use crate::console::mmu;
 use crate::console::mmu::{Caller, Mmu};
 use crate::console::register::Register;
+use crate::console::scheduler::{EventKind, Scheduler};
 
 // const DIV_SPEED: u16 = 256; // 16_384Hz = 256 cpu clocks
 
 #[allow(dead_code)]
 pub(crate) struct Timer {
-    is_in_stop_mode: bool, // TODO implement
-    tima_overflow: bool,
+    is_in_stop_mode: bool, // Set while the CPU is in STOP mode, DIV and TIMA do not advance
+    tima_overflow: bool,   // TIMA overflowed and will be reloaded from TMA by the pending overflow event
+    last_sync: u64,        // Scheduler time the registers were last brought up to date
     // The DIV IO register only exposes the upper 8 bits of system 16bit counter,
     // so its exposed value increases every 256 cycles.
     div: Register,         // FF04 — DIV: Divider register
@@ -23,6 +26,7 @@ impl Timer {
         Timer {
             is_in_stop_mode: false,
             tima_overflow: false,
+            last_sync: 0,
             div: Register::new(mmu::DIV_REG),
             tima: Register::new(mmu::TIMA_REG),
             tma: Register::new(mmu::TMA_REG),
@@ -30,34 +34,31 @@ impl Timer {
         }
     }
 
-    pub(crate) fn is_tac_enabled(tac: u8) -> bool {
-        (tac & 0b0100) == 0b0100
+    pub(crate) fn set_stop_mode(&mut self, mmu: &mut Mmu, scheduler: &mut Scheduler, is_in_stop_mode: bool) {
+        self.sync(mmu, scheduler.now);
+        self.is_in_stop_mode = is_in_stop_mode;
+        self.reschedule(mmu, scheduler);
     }
 
-    pub(crate) fn is_counter_bit_flipped(internal_counter: u16, tac: u8) -> bool {
-        let bit_mask = Timer::counter_bit(tac);
-        (internal_counter & bit_mask) == bit_mask
+    pub(crate) fn is_tac_enabled(tac: u8) -> bool {
+        (tac & 0b0100) == 0b0100
     }
 
-    #[allow(unused_variables)]
-    pub(crate) fn step(&mut self, mmu: &mut Mmu, cycles: u16) -> bool {
-        let mut request_interrupt = false;
+    // Bring the system clock, DIV and TIMA up to date with the scheduler's current time.
+    // Must be called before the CPU reads or writes any of the timer registers.
+    pub(crate) fn sync(&mut self, mmu: &mut Mmu, now: u64) {
+        let cycles = now - self.last_sync;
+        self.last_sync = now;
 
-        // TODO: stop mode
-        if self.is_in_stop_mode {
-            return false;
+        if self.is_in_stop_mode || cycles == 0 {
+            return;
         }
 
-        // Check TAC
-        let tac = self.tac.read(mmu, Caller::TIMER);
-        let tima_incr_is_enabled = Timer::is_tac_enabled(tac);
-
-        // Using TAC, check sysclock counter bit (before clock is incremented)
-        let prev_counter_bit = Timer::is_counter_bit_flipped(mmu.sysclock, tac);
+        let prev_sysclock = mmu.sysclock;
 
         // Increment internal/system clock by cycles, which in turn increments DIV.
         // https://gbdev.io/pandocs/Timer_Obscure_Behaviour.html#relation-between-timer-and-divider-register
-        mmu.sysclock = mmu.sysclock.wrapping_add(cycles);
+        mmu.sysclock = mmu.sysclock.wrapping_add(cycles as u16);
 
         // DIV = top 8 (?) bits of the sysclock, ie sysclock shifted >> 8(?) bits.
         // DIV increments every 256 machine clocks(?)
@@ -67,38 +68,56 @@ impl Timer {
             self.div.write(mmu, new_div, Caller::TIMER);
         }
 
-        // If TIMA overflowed (on last tick), set TIMA to TMA and request interrupt.
-        // When TIMA overflows, the value from TMA is copied, and the timer flag is set in IF, but one M-cycle later.
-        // (This means that TIMA is equal to $00 for the M-cycle after it overflows.)
-        // (This only happens when TIMA overflows from incrementing, it cannot be made to happen by manually writing to TIMA.)
+        // Increment TIMA once every time the TAC selected sysclock bit changes.
+        // Passes all blarrg/cpu_instrs tests, but fails blargg/mem_timing/individual/02-write_timing.gb
+        // (counting only falling edges passes more of it, but fails blarrg/cpu_instrs/individual/02-interrupts.gb)
+        let tac = self.tac.read(mmu, Caller::TIMER);
+        if Timer::is_tac_enabled(tac) {
+            let period = Timer::counter_bit(tac) as u64;
+            let increments = (prev_sysclock as u64 % period + cycles) / period;
+            if increments > 0 {
+                let tima = self.tima.read(mmu, Caller::TIMER);
+                if tima as u64 + increments > 0xFF {
+                    self.tima_overflow = true;
+                }
+                self.tima.write(mmu, tima.wrapping_add(increments as u8), Caller::TIMER);
+            }
+        }
+    }
+
+    // Schedule the next TIMA overflow, must be called (after syncing) whenever DIV, TIMA, TMA or TAC
+    // is written or the stop mode changes.
+    pub(crate) fn reschedule(&mut self, mmu: &mut Mmu, scheduler: &mut Scheduler) {
         if self.tima_overflow {
-            let tma = self.tma.read(mmu, Caller::TIMER);
-            self.tima.write(mmu, tma, Caller::TIMER);
-            self.tima_overflow = false;
-            request_interrupt = true;
+            return; // TIMA already overflowed, the pending reload still happens
         }
-        // Else increment TIMA according to TAC and DIV/sysclock
-        else if tima_incr_is_enabled {
-            let new_counter_bit = Timer::is_counter_bit_flipped(mmu.sysclock, tac);
-
-            let increment_tima =
-                // Option 1: different than before -- Passes all blarrg/cpu_instrs tests!
-                // but fails blargg/mem_timing/individual/02-write_timing.gb
-                prev_counter_bit != new_counter_bit;
-
-                // Option 2: was flipped last tick but now isn't
-                // passes more of blargg/mem_timing/individual/02-write_timing.gb??,
-                // but fails blarrg/cpu_instrs/individual/02-interrupts.gb!! >:(
-                // prev_counter_bit && !new_counter_bit;
-
-            if increment_tima {
-                let mut tima = self.tima.read(mmu, Caller::TIMER);
-                (tima, self.tima_overflow) = tima.overflowing_add(1);
-                self.tima.write(mmu, tima, Caller::TIMER);
-            }
+
+        let tac = self.tac.read(mmu, Caller::TIMER);
+        if self.is_in_stop_mode || !Timer::is_tac_enabled(tac) {
+            scheduler.cancel(EventKind::TimerOverflow);
+            return;
         }
 
-        request_interrupt
+        let period = Timer::counter_bit(tac) as u64;
+        let tima = self.tima.read(mmu, Caller::TIMER) as u64;
+        let until_next_increment = period - mmu.sysclock as u64 % period;
+        let until_overflow = until_next_increment + (0xFF - tima) * period;
+
+        // When TIMA overflows, the value from TMA is copied, and the timer flag is set in IF, but one M-cycle later.
+        // (This means that TIMA is equal to $00 for the M-cycle after it overflows.)
+        scheduler.schedule(EventKind::TimerOverflow, until_overflow + M_CYCLE as u64);
+    }
+
+    // Handles EventKind::TimerOverflow: set TIMA to TMA and returns true to request the timer interrupt.
+    pub(crate) fn overflow(&mut self, mmu: &mut Mmu, scheduler: &mut Scheduler) -> bool {
+        self.sync(mmu, scheduler.now);
+
+        let tma = self.tma.read(mmu, Caller::TIMER);
+        self.tima.write(mmu, tma, Caller::TIMER);
+        self.tima_overflow = false;
+
+        self.reschedule(mmu, scheduler);
+        true
     }
 
     pub(crate) fn as_str(&mut self, mmu: &mut Mmu) -> String {
@@ -129,3 +148,86 @@ impl Timer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::console::bus::Bus;
+    use crate::console::interrupts::InterruptRegBit;
+    use crate::console::ppu::Ppu;
+    use crate::console::serial::Serial;
+    use super::*;
+
+    const TAC_ENABLED: u8 = 0b101;
+    // TIMA counts both edges of the TAC selected sysclock bit (bit 3 here), see `sync`
+    const PERIOD: i16 = 8;
+
+    struct System {
+        mmu: Mmu,
+        ppu: Ppu,
+        timer: Timer,
+        serial: Serial,
+        scheduler: Scheduler,
+    }
+
+    impl System {
+        fn new() -> System {
+            System {
+                mmu: Mmu::new(None, true),
+                ppu: Ppu::new(),
+                timer: Timer::new(),
+                serial: Serial::new(),
+                scheduler: Scheduler::new(),
+            }
+        }
+
+        // Time and the system clock start at 0, every access ticks an M-cycle first
+        fn bus(&mut self) -> Bus<'_> {
+            Bus::new(&mut self.mmu, &mut self.ppu, &mut self.timer, &mut self.serial, &mut self.scheduler)
+        }
+    }
+
+    // Ticks M-cycles until the timer interrupt is requested, returns the time it was requested at
+    fn timer_interrupt_time(bus: &mut Bus) -> i16 {
+        while bus.mmu.read_8(mmu::IF_REG, Caller::CPU) & (1 << InterruptRegBit::Timer as u8) == 0 {
+            assert!(bus.elapsed < 10_000, "the timer interrupt was never requested");
+            bus.tick(M_CYCLE);
+        }
+        bus.elapsed
+    }
+
+    #[test]
+    fn overflow_reloads_tma_and_requests_interrupt() {
+        let mut system = System::new();
+        let mut bus = system.bus();
+        bus.write_8(mmu::TMA_REG, 0xF0, Caller::CPU);       // t=4
+        bus.write_8(mmu::TIMA_REG, 0xFE, Caller::CPU);      // t=8
+        bus.write_8(mmu::TAC_REG, TAC_ENABLED, Caller::CPU); // t=12
+
+        // TIMA goes to 0xFF at 16 and overflows at 24, the reload happens an M-cycle later
+        assert_eq!(timer_interrupt_time(&mut bus), 16 + PERIOD + 4);
+        assert_eq!(bus.mmu.read_8(mmu::TIMA_REG, Caller::CPU), 0xF0);
+    }
+
+    #[test]
+    fn overflow_follows_tima_writes() {
+        // Written halfway through a period, after an overflow was already scheduled for 28
+        let mut system = System::new();
+        let mut bus = system.bus();
+        bus.write_8(mmu::TIMA_REG, 0xFE, Caller::CPU);      // t=4
+        bus.write_8(mmu::TAC_REG, TAC_ENABLED, Caller::CPU); // t=8
+        bus.tick(2 * M_CYCLE);
+        bus.write_8(mmu::TIMA_REG, 0xF0, Caller::CPU);      // t=20, TIMA was 0xFF
+
+        // 0xF1 at 24, then 15 more increments
+        assert_eq!(timer_interrupt_time(&mut bus), 24 + 15 * PERIOD + 4);
+
+        // Written halfway through a period, long before the scheduled overflow
+        let mut system = System::new();
+        let mut bus = system.bus();
+        bus.write_8(mmu::TAC_REG, TAC_ENABLED, Caller::CPU); // t=4
+        bus.tick(7 * M_CYCLE);
+        bus.write_8(mmu::TIMA_REG, 0xFF, Caller::CPU);      // t=36
+
+        assert_eq!(timer_interrupt_time(&mut bus), 40 + 4);
+    }
+}
@@ -0,0 +1,245 @@
+// Pixel-art upscalers, run on the RGBA8888 frame before it is handed to the renderer so it still
+// looks sharp when the window is much larger than the LCD. The renderer scales whatever comes out
+// to the window, so Nearest leaves the frame as it is.
+// https://www.scale2x.it/algorithm
+// https://en.wikipedia.org/wiki/Pixel-art_scaling_algorithms
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) enum UpscalerKind {
+    Nearest,
+    Scale2x,
+    Scale3x,
+    Eagle,
+    Xbr, // xBR level 1 rules at 2x, blending half of each corner
+}
+
+const KINDS: [UpscalerKind; 5] = [
+    UpscalerKind::Nearest,
+    UpscalerKind::Scale2x,
+    UpscalerKind::Scale3x,
+    UpscalerKind::Eagle,
+    UpscalerKind::Xbr,
+];
+
+impl UpscalerKind {
+    pub(crate) fn parse(name: &str) -> Option<UpscalerKind> {
+        KINDS.iter()
+            .find(|kind| kind.name().eq_ignore_ascii_case(name))
+            .copied()
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        match self {
+            UpscalerKind::Nearest => "nearest",
+            UpscalerKind::Scale2x => "scale2x",
+            UpscalerKind::Scale3x => "scale3x",
+            UpscalerKind::Eagle => "eagle",
+            UpscalerKind::Xbr => "xbr",
+        }
+    }
+
+    fn scale(&self) -> usize {
+        match self {
+            UpscalerKind::Nearest => 1,
+            UpscalerKind::Scale2x | UpscalerKind::Eagle | UpscalerKind::Xbr => 2,
+            UpscalerKind::Scale3x => 3,
+        }
+    }
+}
+
+// Pixels are compared as whole u32s
+struct Frame<'a> {
+    pixels: &'a [u32],
+    width: usize,
+    height: usize,
+}
+
+impl Frame<'_> {
+    // Neighbours past the edges repeat the edge pixel
+    fn get(&self, x: usize, y: usize, dx: isize, dy: isize) -> u32 {
+        let x = (x as isize + dx).clamp(0, self.width as isize - 1) as usize;
+        let y = (y as isize + dy).clamp(0, self.height as isize - 1) as usize;
+        self.pixels[y * self.width + x]
+    }
+}
+
+pub(crate) struct Upscaler {
+    kind: UpscalerKind,
+    pixels: Vec<u32>,
+    scaled: Vec<u8>,
+}
+
+impl Upscaler {
+    pub(crate) fn new(kind: UpscalerKind) -> Upscaler {
+        Upscaler {
+            kind,
+            pixels: vec![],
+            scaled: vec![],
+        }
+    }
+
+    pub(crate) fn kind(&self) -> UpscalerKind {
+        self.kind
+    }
+
+    pub(crate) fn cycle(&mut self) {
+        let i = KINDS.iter().position(|&kind| kind == self.kind).unwrap();
+        self.kind = KINDS[(i + 1) % KINDS.len()];
+    }
+
+    // How many times larger the frame comes out of `apply`
+    pub(crate) fn scale(&self) -> u32 {
+        self.kind.scale() as u32
+    }
+
+    // Returns the upscaled RGBA8888 frame, `scale()` times the size of `rgba`
+    pub(crate) fn apply<'a>(&'a mut self, rgba: &'a [u8], width: usize, height: usize) -> &'a [u8] {
+        if self.kind == UpscalerKind::Nearest {
+            return rgba;
+        }
+
+        self.pixels.clear();
+        self.pixels.extend(rgba.chunks_exact(4).take(width * height).map(|p| u32::from_ne_bytes([p[0], p[1], p[2], p[3]])));
+        let frame = Frame {
+            pixels: &self.pixels,
+            width,
+            height,
+        };
+
+        let scale = self.kind.scale();
+        let scaled_width = width * scale;
+        self.scaled.resize(scaled_width * height * scale * 4, 0);
+
+        for y in 0..height {
+            for x in 0..width {
+                // Row-major, `scale` x `scale`
+                let block = match self.kind {
+                    UpscalerKind::Nearest => [frame.get(x, y, 0, 0); 9],
+                    UpscalerKind::Scale2x => Upscaler::scale2x(&frame, x, y),
+                    UpscalerKind::Scale3x => Upscaler::scale3x(&frame, x, y),
+                    UpscalerKind::Eagle => Upscaler::eagle(&frame, x, y),
+                    UpscalerKind::Xbr => Upscaler::xbr(&frame, x, y),
+                };
+                for by in 0..scale {
+                    for bx in 0..scale {
+                        let i = ((y * scale + by) * scaled_width + x * scale + bx) * 4;
+                        self.scaled[i..i + 4].copy_from_slice(&block[by * scale + bx].to_ne_bytes());
+                    }
+                }
+            }
+        }
+        &self.scaled
+    }
+
+    //   A
+    // C P B
+    //   D
+    fn scale2x(frame: &Frame, x: usize, y: usize) -> [u32; 9] {
+        let p = frame.get(x, y, 0, 0);
+        let a = frame.get(x, y, 0, -1);
+        let b = frame.get(x, y, 1, 0);
+        let c = frame.get(x, y, -1, 0);
+        let d = frame.get(x, y, 0, 1);
+
+        let mut block = [p; 9];
+        if c == a && c != d && a != b { block[0] = a; }
+        if a == b && a != c && b != d { block[1] = b; }
+        if d == c && d != b && c != a { block[2] = c; }
+        if b == d && b != a && d != c { block[3] = d; }
+        block
+    }
+
+    // A B C
+    // D E F
+    // G H I
+    fn scale3x(frame: &Frame, x: usize, y: usize) -> [u32; 9] {
+        let a = frame.get(x, y, -1, -1);
+        let b = frame.get(x, y, 0, -1);
+        let c = frame.get(x, y, 1, -1);
+        let d = frame.get(x, y, -1, 0);
+        let e = frame.get(x, y, 0, 0);
+        let f = frame.get(x, y, 1, 0);
+        let g = frame.get(x, y, -1, 1);
+        let h = frame.get(x, y, 0, 1);
+        let i = frame.get(x, y, 1, 1);
+
+        if b == h || d == f {
+            return [e; 9];
+        }
+        [
+            if d == b { d } else { e },
+            if (d == b && e != c) || (b == f && e != a) { b } else { e },
+            if b == f { f } else { e },
+            if (d == b && e != g) || (d == h && e != a) { d } else { e },
+            e,
+            if (b == f && e != i) || (h == f && e != c) { f } else { e },
+            if d == h { d } else { e },
+            if (d == h && e != i) || (h == f && e != g) { h } else { e },
+            if h == f { f } else { e },
+        ]
+    }
+
+    // Each corner takes the color of its 3 outer neighbours when they all match
+    fn eagle(frame: &Frame, x: usize, y: usize) -> [u32; 9] {
+        let p = frame.get(x, y, 0, 0);
+        let mut block = [p; 9];
+        for (i, (dx, dy)) in [(-1, -1), (1, -1), (-1, 1), (1, 1)].into_iter().enumerate() {
+            let corner = frame.get(x, y, dx, dy);
+            if corner == frame.get(x, y, dx, 0) && corner == frame.get(x, y, 0, dy) {
+                block[i] = corner;
+            }
+        }
+        block
+    }
+
+    //       A1 B1 C1
+    //    A0 A  B  C  C4
+    //    D0 D  E  F  F4
+    //    G0 G  H  I  I4
+    //       G5 H5 I5
+    // Written for the bottom right corner, the others mirror the neighbourhood.
+    fn xbr(frame: &Frame, x: usize, y: usize) -> [u32; 9] {
+        let e = frame.get(x, y, 0, 0);
+        let mut block = [e; 9];
+        for (i, (sx, sy)) in [(-1, -1), (1, -1), (-1, 1), (1, 1)].into_iter().enumerate() {
+            let get = |dx: isize, dy: isize| frame.get(x, y, dx * sx, dy * sy);
+            let (b, c, d, f) = (get(0, -1), get(1, -1), get(-1, 0), get(1, 0));
+            let (g, h, i_, f4) = (get(-1, 1), get(0, 1), get(1, 1), get(2, 0));
+            let (i4, h5, i5) = (get(2, 1), get(0, 2), get(1, 2));
+
+            if e == f || e == h {
+                continue;
+            }
+            let dist = Upscaler::color_distance;
+            let edge_along = dist(e, c) + dist(e, g) + dist(i_, f4) + dist(i_, h5) + 4 * dist(h, f);
+            let edge_across = dist(h, d) + dist(h, i5) + dist(f, i4) + dist(f, b) + 4 * dist(e, i_);
+            if edge_along < edge_across {
+                let new_color = if dist(e, f) <= dist(e, h) { f } else { h };
+                block[i] = Upscaler::blend_half(e, new_color);
+            }
+        }
+        block
+    }
+
+    // Weighted YUV difference, luma counts the most
+    fn color_distance(a: u32, b: u32) -> u32 {
+        let yuv = |pixel: u32| {
+            let [r, g, b, _] = pixel.to_ne_bytes();
+            let (r, g, b) = (r as i32, g as i32, b as i32);
+            ((r * 299 + g * 587 + b * 114) / 1000, (b - g) / 2, (r - g) / 2)
+        };
+        let (ya, ua, va) = yuv(a);
+        let (yb, ub, vb) = yuv(b);
+        ((ya - yb).unsigned_abs() * 48) + ((ua - ub).unsigned_abs() * 7) + ((va - vb).unsigned_abs() * 6)
+    }
+
+    fn blend_half(a: u32, b: u32) -> u32 {
+        let (a, b) = (a.to_ne_bytes(), b.to_ne_bytes());
+        u32::from_ne_bytes([
+            ((a[0] as u16 + b[0] as u16) / 2) as u8,
+            ((a[1] as u16 + b[1] as u16) / 2) as u8,
+            ((a[2] as u16 + b[2] as u16) / 2) as u8,
+            ((a[3] as u16 + b[3] as u16) / 2) as u8,
+        ])
+    }
+}